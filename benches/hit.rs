@@ -0,0 +1,136 @@
+//! Closest-hit and any-hit throughput over a binary and a 4-wide BVH of
+//! spheres and quads, and of the 4-wide one tracing camera rays in packets.
+//!
+//! Run with `cargo bench --bench hit`.
+
+use std::hint::black_box;
+use std::rc::Rc;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rust_tracer::color::Color;
+use rust_tracer::hit_record::HitRecord;
+use rust_tracer::hittable::{Bvh4, BvhNode, Hittable, HittableList, Quad, Sphere, PACKET_SIZE};
+use rust_tracer::interval::Interval;
+use rust_tracer::material::Lambertian;
+use rust_tracer::point3::Point3;
+use rust_tracer::ray::Ray;
+use rust_tracer::vec3::Vec3;
+
+const RAYS: usize = 2_000_000;
+/// Camera rays come in square tiles, as many as fit in a packet.
+const TILE: usize = 8;
+
+fn scene(rng: &mut StdRng) -> HittableList {
+    let material = Rc::new(Lambertian::new_with_color(Color::new(0.5, 0.5, 0.5)));
+    let mut world = HittableList::default();
+    for _ in 0..2000 {
+        let center = Point3::new(
+            rng.gen_range(-20.0..20.0),
+            rng.gen_range(-20.0..20.0),
+            rng.gen_range(-20.0..20.0),
+        );
+        if rng.gen_bool(0.8) {
+            world.add(Rc::new(Sphere::new(center, 0.5, material.clone()).unwrap()));
+        } else {
+            world.add(Rc::new(
+                Quad::new(
+                    center,
+                    Vec3::new(1.0, 0.0, 0.0),
+                    Vec3::new(0.0, 1.0, 0.0),
+                    material.clone(),
+                )
+                .unwrap(),
+            ));
+        }
+    }
+    world
+}
+
+fn main() {
+    let bvh = BvhNode::from_hittable_list(scene(&mut StdRng::seed_from_u64(1)));
+    let bvh4 = Bvh4::from_hittable_list(scene(&mut StdRng::seed_from_u64(1)));
+    let mut rng = StdRng::seed_from_u64(2);
+    let rays: Vec<Ray> = (0..RAYS)
+        .map(|_| {
+            let direction = Vec3::new(rng.gen_range(-0.6..0.6), rng.gen_range(-0.6..0.6), 1.0);
+            Ray::new(Point3::new(0.0, 0.0, -30.0), direction, 0.0)
+        })
+        .collect();
+
+    // A grid of directions over the same field of view, tile by tile.
+    let side = (RAYS as f64).sqrt() as usize / TILE * TILE;
+    let mut camera_rays = Vec::with_capacity(side * side);
+    for tile_y in (0..side).step_by(TILE) {
+        for tile_x in (0..side).step_by(TILE) {
+            for y in tile_y..tile_y + TILE {
+                for x in tile_x..tile_x + TILE {
+                    let s = 1.2 * (x as f64 + 0.5) / side as f64 - 0.6;
+                    let t = 1.2 * (y as f64 + 0.5) / side as f64 - 0.6;
+                    let direction = Vec3::new(s, t, 1.0);
+                    camera_rays.push(Ray::new(Point3::new(0.0, 0.0, -30.0), direction, 0.0));
+                }
+            }
+        }
+    }
+
+    for _ in 0..3 {
+        for (name, world) in [("bvh", &bvh as &dyn Hittable), ("bvh4", &bvh4)] {
+            report(&format!("{} closest hit", name), &rays, |r| {
+                closest_hit(world, r)
+            });
+            report(&format!("{} any hit", name), &rays, |r| {
+                world.occluded(r, Interval::new(0.001, f64::INFINITY))
+            });
+            report(&format!("{} camera rays", name), &camera_rays, |r| {
+                closest_hit(world, r)
+            });
+        }
+
+        let start = Instant::now();
+        let mut hits = 0;
+        let mut recs = [HitRecord::default(); PACKET_SIZE];
+        for packet in camera_rays.chunks(PACKET_SIZE) {
+            let mask = bvh4.hit_packet(packet, Interval::new(0.001, f64::INFINITY), &mut recs);
+            for (k, r) in packet.iter().enumerate() {
+                if mask >> k & 1 != 0 {
+                    recs[k].finish(r);
+                }
+            }
+            hits += mask.count_ones() as usize;
+            black_box(&recs);
+        }
+        print_rate("bvh4 camera packets", camera_rays.len(), hits, start);
+    }
+}
+
+fn closest_hit(world: &dyn Hittable, r: &Ray) -> bool {
+    let mut rec = HitRecord::default();
+    let hit = world.hit(r, Interval::new(0.001, f64::INFINITY), &mut rec);
+    if hit {
+        rec.finish(r);
+    }
+    black_box(&rec);
+    hit
+}
+
+/// Times `trace` over every ray, which returns whether the ray hit anything.
+fn report(name: &str, rays: &[Ray], mut trace: impl FnMut(&Ray) -> bool) {
+    let start = Instant::now();
+    let hits = rays.iter().filter(|r| trace(r)).count();
+    print_rate(name, rays.len(), hits, start);
+}
+
+fn print_rate(name: &str, rays: usize, hits: usize, start: Instant) {
+    let elapsed = start.elapsed();
+    println!(
+        "{}: {} rays, {} hits in {:.3} s: {:.2} Mrays/s",
+        name,
+        rays,
+        hits,
+        elapsed.as_secs_f64(),
+        rays as f64 / elapsed.as_secs_f64() / 1e6
+    );
+}
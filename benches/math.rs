@@ -0,0 +1,81 @@
+//! Throughput of the vector types and the ray/box slab test.
+//!
+//! Run with `cargo bench --bench math`, adding `--features f32` for single
+//! precision.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rust_tracer::aabb::Aabb;
+use rust_tracer::color::Color;
+use rust_tracer::interval::Interval;
+use rust_tracer::point3::Point3;
+use rust_tracer::ray::Ray;
+use rust_tracer::vec3::Vec3;
+
+const VECTORS: usize = 1 << 20;
+const RAYS: usize = 1 << 18;
+const BOXES: usize = 64;
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut vector = || {
+        Vec3::new(
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+            rng.gen_range(-1.0..1.0),
+        )
+    };
+    let vectors: Vec<(Vec3, Vec3)> = (0..VECTORS).map(|_| (vector(), vector())).collect();
+    let boxes: Vec<Aabb> = (0..BOXES)
+        .map(|_| {
+            let a = Point3::new(0.0, 0.0, 0.0) + 10.0 * vector();
+            Aabb::from_points(a, a + vector())
+        })
+        .collect();
+    let rays: Vec<Ray> = (0..RAYS)
+        .map(|_| Ray::new(Point3::new(0.0, 0.0, -20.0), vector(), 0.0))
+        .collect();
+
+    for _ in 0..3 {
+        report("vector ops", VECTORS, || {
+            let mut sum = Vec3::default();
+            let mut color = Color::default();
+            for (a, b) in &vectors {
+                let n = a.cross(b).unit_vector();
+                sum = sum + a.reflected(&n) * a.dot(b);
+                color += Color::new(n.x(), n.y(), n.z()) * Color::new(0.5, 0.25, 0.125);
+            }
+            black_box((sum, color));
+        });
+        report("slab tests", RAYS * BOXES, || {
+            let mut hits = 0;
+            for r in &rays {
+                for b in &boxes {
+                    let mut ray_t = Interval::new(0.001, f64::INFINITY);
+                    if b.hit(r, &mut ray_t) {
+                        hits += 1;
+                    }
+                }
+            }
+            black_box(hits);
+        });
+    }
+}
+
+/// Times `run`, which performs `count` operations.
+fn report(name: &str, count: usize, run: impl FnOnce()) {
+    let start = Instant::now();
+    run();
+    let elapsed = start.elapsed();
+    println!(
+        "{}: {} in {:.3} s: {:.1} M/s",
+        name,
+        count,
+        elapsed.as_secs_f64(),
+        count as f64 / elapsed.as_secs_f64() / 1e6
+    );
+}
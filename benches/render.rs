@@ -0,0 +1,108 @@
+//! End-to-end render time of a random spheres scene, through every shading
+//! path: diffuse, metal and glass, with a textured ground.
+//!
+//! Run with `cargo bench --bench render`, adding `--features f32` for single
+//! precision.
+
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rust_tracer::camera::Camera;
+use rust_tracer::color::Color;
+use rust_tracer::hittable::{BvhNode, HittableList, Sphere};
+use rust_tracer::material::{Dielectric, Lambertian, Material, Metal};
+use rust_tracer::point3::Point3;
+use rust_tracer::sampler::IndependentSampler;
+use rust_tracer::texture::CheckerTexture;
+use rust_tracer::vec3::Vec3;
+use rust_tracer::Result;
+
+const RUNS: usize = 3;
+
+fn scene() -> Result<HittableList> {
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut world = HittableList::default();
+    let checker = Rc::new(Lambertian::new(Rc::new(CheckerTexture::new_with_colours(
+        0.32,
+        Color::new(0.2, 0.3, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ))));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        checker,
+    )?));
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let center = Point3::new(
+                a as f64 + 0.9 * rng.gen::<f64>(),
+                0.2,
+                b as f64 + 0.9 * rng.gen::<f64>(),
+            );
+            let choice: f64 = rng.gen();
+            let material: Rc<dyn Material> = if choice < 0.7 {
+                let albedo = Color::new(rng.gen(), rng.gen(), rng.gen());
+                Rc::new(Lambertian::new_with_color(albedo))
+            } else if choice < 0.9 {
+                let albedo = Color::new(rng.gen(), rng.gen(), rng.gen());
+                Rc::new(Metal::new(albedo, 0.3 * rng.gen::<f64>())?)
+            } else {
+                Rc::new(Dielectric::new(1.5)?)
+            };
+            world.add(Rc::new(Sphere::new(center, 0.2, material)?));
+        }
+    }
+
+    let big: [(f64, Rc<dyn Material>); 3] = [
+        (0.0, Rc::new(Dielectric::new(1.5)?)),
+        (
+            -4.0,
+            Rc::new(Lambertian::new_with_color(Color::new(0.4, 0.2, 0.1))),
+        ),
+        (4.0, Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0)?)),
+    ];
+    for (x, material) in big {
+        world.add(Rc::new(Sphere::new(
+            Point3::new(x, 1.0, 0.0),
+            1.0,
+            material,
+        )?));
+    }
+    Ok(world)
+}
+
+fn main() -> Result<()> {
+    let world = BvhNode::from_hittable_list(scene()?);
+    let mut camera = Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(240)
+        .samples_per_pixel(48)
+        .max_depth(20)
+        .vfov(20.0)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .lookfrom(Point3::new(13.0, 2.0, 3.0))
+        .lookat(Point3::new(0.0, 0.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .sampler(Rc::new(IndependentSampler::new(3)));
+    let (width, height) = camera.image_size()?;
+
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        camera.render_tile(0..width, 0..height, &world)?;
+        let elapsed = start.elapsed();
+        best = best.min(elapsed);
+        println!(
+            "render: {}x{}: {:.3} s",
+            width,
+            height,
+            elapsed.as_secs_f64()
+        );
+    }
+    println!("best of {}: {:.3} s", RUNS, best.as_secs_f64());
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use crate::interval::Interval;
 use crate::point3::Point3;
 use crate::ray::Ray;
-use std::mem::swap;
+use crate::simd::F3;
+use crate::vec3::Vec3;
 
 #[derive(Debug, Copy, Clone, Default)]
 pub struct Aabb {
@@ -23,6 +24,13 @@ impl Aabb {
         }
     }
 
+    /// Bounds of the disk around `center` with unit `normal` and `radius`.
+    pub fn from_disk(center: Point3, normal: Vec3, radius: f64) -> Self {
+        let extent = |n: f64| radius * (1.0 - n * n).max(0.0).sqrt();
+        let e = Vec3::new(extent(normal.x()), extent(normal.y()), extent(normal.z()));
+        Self::from_points(center - e, center + e)
+    }
+
     pub fn from_boxes(box0: Aabb, box1: Aabb) -> Self {
         Self {
             x: box0.x.combined(box1.x),
@@ -31,6 +39,14 @@ impl Aabb {
         }
     }
 
+    pub fn intersection(&self, other: &Aabb) -> Self {
+        Self {
+            x: self.x.intersection(other.x),
+            y: self.y.intersection(other.y),
+            z: self.z.intersection(other.z),
+        }
+    }
+
     pub fn axis(&self, n: i32) -> &Interval {
         if n == 1 {
             &self.y
@@ -41,30 +57,25 @@ impl Aabb {
         }
     }
 
+    /// Clips `ray_t` to the stretch of `r` inside the box, testing all three
+    /// slabs at once.
+    #[inline]
     pub fn hit(&self, r: &Ray, ray_t: &mut Interval) -> bool {
-        for a in 0..3 {
-            let inv_d = 1.0 / r.direction()[a];
-            let orig = r.origin()[a];
-
-            let mut t0 = (self.axis(a).min - orig) * inv_d;
-            let mut t1 = (self.axis(a).max - orig) * inv_d;
+        let min = F3::new(self.x.min, self.y.min, self.z.min);
+        let max = F3::new(self.x.max, self.y.max, self.z.max);
+        let origin = r.origin().lanes();
+        let inv_d = r.inv_direction().lanes();
 
-            if inv_d < 0.0 {
-                swap(&mut t0, &mut t1)
-            }
+        let t0 = (min - origin) * inv_d;
+        let t1 = (max - origin) * inv_d;
 
-            if t0 > ray_t.min {
-                ray_t.min = t0
-            };
-            if t1 < ray_t.max {
-                ray_t.max = t1
-            };
-
-            if ray_t.max <= ray_t.min {
-                return false;
-            }
-        }
-        true
+        // A ray lying in a face's plane gives 0 * inf = NaN there, and counts
+        // as inside the slab, whichever face it is.
+        let near = t0.slab_near(t1, F3::scalar(ray_t.min)).max_component();
+        let far = t0.slab_far(t1, F3::scalar(ray_t.max)).min_component();
+        ray_t.min = near;
+        ray_t.max = far;
+        far > near
     }
 
     pub fn pad(&self) -> Aabb {
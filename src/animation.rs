@@ -0,0 +1,124 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::camera::Camera;
+use crate::error::{Error, Result};
+use crate::hittable::Hittable;
+use crate::keyframes::Keyframes;
+use crate::point3::Point3;
+
+/// Renders a range of frames, keyframing the camera over scene time.
+///
+/// Frame `n` is exposed from `n / fps`, so objects wrapped in
+/// [`Animated`](crate::hittable::Animated) move with the same clock. Tracks
+/// left unset keep the value configured on the camera.
+pub struct Animation {
+    frames: Range<i32>,
+    fps: f64,
+    shutter: f64,
+    skip_existing: bool,
+
+    lookfrom: Option<Keyframes<Point3>>,
+    lookat: Option<Keyframes<Point3>>,
+    vfov: Option<Keyframes<f64>>,
+    focus_dist: Option<Keyframes<f64>>,
+    defocus_angle: Option<Keyframes<f64>>,
+}
+
+impl Animation {
+    pub fn new(frames: Range<i32>, fps: f64) -> Result<Self> {
+        Error::check_positive("frames per second", fps)?;
+        Ok(Self {
+            frames,
+            fps,
+            shutter: 0.5,
+            skip_existing: false,
+            lookfrom: None,
+            lookat: None,
+            vfov: None,
+            focus_dist: None,
+            defocus_angle: None,
+        })
+    }
+
+    /// The fraction of a frame the shutter stays open for.
+    pub fn shutter(mut self, shutter: f64) -> Self {
+        self.shutter = shutter;
+        self
+    }
+
+    /// Leave frames whose output file already exists alone, to resume a render.
+    pub fn skip_existing(mut self, skip_existing: bool) -> Self {
+        self.skip_existing = skip_existing;
+        self
+    }
+
+    pub fn lookfrom(mut self, lookfrom: Keyframes<Point3>) -> Self {
+        self.lookfrom = Some(lookfrom);
+        self
+    }
+
+    pub fn lookat(mut self, lookat: Keyframes<Point3>) -> Self {
+        self.lookat = Some(lookat);
+        self
+    }
+
+    pub fn vfov(mut self, vfov: Keyframes<f64>) -> Self {
+        self.vfov = Some(vfov);
+        self
+    }
+
+    pub fn focus_dist(mut self, focus_dist: Keyframes<f64>) -> Self {
+        self.focus_dist = Some(focus_dist);
+        self
+    }
+
+    pub fn defocus_angle(mut self, defocus_angle: Keyframes<f64>) -> Self {
+        self.defocus_angle = Some(defocus_angle);
+        self
+    }
+
+    fn camera_at(&self, camera: &Camera, frame: i32) -> Camera {
+        let open = frame as f64 / self.fps;
+        let close = open + self.shutter / self.fps;
+        let time = (open + close) / 2.0;
+
+        let mut camera = camera.clone().shutter(open, close);
+        if let Some(lookfrom) = &self.lookfrom {
+            camera = camera.lookfrom(lookfrom.at(time));
+        }
+        if let Some(lookat) = &self.lookat {
+            camera = camera.lookat(lookat.at(time));
+        }
+        if let Some(vfov) = &self.vfov {
+            camera = camera.vfov(vfov.at(time));
+        }
+        if let Some(focus_dist) = &self.focus_dist {
+            camera = camera.focus_dist(focus_dist.at(time));
+        }
+        if let Some(defocus_angle) = &self.defocus_angle {
+            camera = camera.defocus_angle(defocus_angle.at(time));
+        }
+        camera
+    }
+
+    /// Writes each frame to `{prefix}{frame:04}.ppm`.
+    pub fn render(&self, camera: &Camera, prefix: &str, world: &impl Hittable) -> Result<()> {
+        for frame in self.frames.clone() {
+            let file_name = format!("{}{:04}.ppm", prefix, frame);
+            if self.skip_existing && Path::new(&file_name).exists() {
+                camera
+                    .observer()
+                    .message(&format!("Skipping frame {}, {} exists", frame, file_name));
+                continue;
+            }
+
+            camera
+                .observer()
+                .message(&format!("Frame {}/{}", frame, self.frames.end - 1));
+            self.camera_at(camera, frame).render(&file_name, world)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+use crate::aperture::Aperture;
+use crate::vec3::Vec3;
+
+#[derive(Default)]
+pub struct Circular;
+
+impl Aperture for Circular {
+    fn sample(&self, u: (f64, f64)) -> (f64, f64) {
+        let p = Vec3::sample_in_unit_disk(u);
+        (p.x(), p.y())
+    }
+}
@@ -0,0 +1,54 @@
+use image::{open, GrayImage};
+
+use crate::aperture::Aperture;
+use crate::error::{Error, Result};
+
+/// An aperture shaped by a grayscale image, where white is open and black is
+/// blocked. The image is fitted into the unit disk, centred.
+pub struct MaskAperture {
+    image: GrayImage,
+    cdf: Vec<f64>,
+}
+
+impl MaskAperture {
+    pub fn new(filename: &str) -> Result<Self> {
+        let image = open(filename)?.into_luma8();
+
+        let mut total = 0.0;
+        let cdf = image
+            .pixels()
+            .map(|pixel| {
+                total += pixel.0[0] as f64;
+                total
+            })
+            .collect();
+
+        if total <= 0.0 {
+            return Err(Error::invalid(format!(
+                "aperture mask {} has no open pixels",
+                filename
+            )));
+        }
+
+        Ok(Self { image, cdf })
+    }
+}
+
+impl Aperture for MaskAperture {
+    fn sample(&self, (u1, u2): (f64, f64)) -> (f64, f64) {
+        let image = &self.image;
+        let target = u1 * self.cdf[self.cdf.len() - 1];
+        let index = self.cdf.partition_point(|c| *c <= target);
+        let below = if index == 0 { 0.0 } else { self.cdf[index - 1] };
+        let jitter = (target - below) / (self.cdf[index] - below);
+        let index = index as u32;
+        let (i, j) = (index % image.width(), index / image.width());
+
+        // Fit the image's diagonal into the unit circle.
+        let (w, h) = (image.width() as f64, image.height() as f64);
+        let scale = 2.0 / (w * w + h * h).sqrt();
+        let x = (i as f64 + jitter - w / 2.0) * scale;
+        let y = (h / 2.0 - j as f64 - u2) * scale;
+        (x, y)
+    }
+}
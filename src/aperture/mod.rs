@@ -0,0 +1,13 @@
+mod circular;
+mod mask;
+mod polygon;
+
+pub use circular::Circular;
+pub use mask::MaskAperture;
+pub use polygon::Polygon;
+
+/// The shape of the lens opening, which is the shape out-of-focus highlights take.
+pub trait Aperture {
+    /// A point on the opening, within the unit disk, for a uniform 2D sample.
+    fn sample(&self, u: (f64, f64)) -> (f64, f64);
+}
@@ -0,0 +1,45 @@
+use std::f64::consts::PI;
+
+use crate::aperture::Aperture;
+use crate::error::{Error, Result};
+
+/// A regular polygon inscribed in the unit circle, as formed by straight
+/// diaphragm blades, turned by `rotation` degrees.
+pub struct Polygon {
+    blades: u32,
+    rotation: f64,
+}
+
+impl Polygon {
+    pub fn new(blades: u32, rotation: f64) -> Result<Self> {
+        if blades < 3 {
+            return Err(Error::invalid(format!(
+                "an aperture needs at least three blades, got {}",
+                blades
+            )));
+        }
+        Ok(Self {
+            blades,
+            rotation: rotation.to_radians(),
+        })
+    }
+
+    fn vertex(&self, k: u32) -> (f64, f64) {
+        let angle = self.rotation + 2.0 * PI * k as f64 / self.blades as f64;
+        (angle.cos(), angle.sin())
+    }
+}
+
+impl Aperture for Polygon {
+    fn sample(&self, (u1, u2): (f64, f64)) -> (f64, f64) {
+        // Pick a triangle between the centre and one edge, then reuse what is
+        // left of the first dimension to place the point inside it.
+        let scaled = u1 * self.blades as f64;
+        let k = (scaled as u32).min(self.blades - 1);
+        let (a, b) = (self.vertex(k), self.vertex(k + 1));
+
+        let s = (scaled - k as f64).sqrt();
+        let (r1, r2) = (s * (1.0 - u2), s * u2);
+        (r1 * a.0 + r2 * b.0, r1 * a.1 + r2 * b.1)
+    }
+}
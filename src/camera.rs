@@ -1,32 +1,69 @@
+use std::cell::Cell;
 use std::cmp::max;
 use std::fs::File;
-use std::io::{stdout, Write};
-use std::time::Instant;
-
-use rand::Rng;
+use std::io::Write;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+use crate::aperture::{Aperture, Circular};
 use crate::color::Color;
+use crate::error::{Error, Result};
+use crate::filter::{BoxFilter, Filter, FilterSampler};
 use crate::hit_record::HitRecord;
-use crate::hittable::Hittable;
+use crate::hittable::{Hittable, PACKET_SIZE};
 use crate::interval::Interval;
+use crate::keyframes::Keyframes;
+use crate::medium::MediumStack;
 use crate::point3::Point3;
+use crate::preview::PreviewServer;
+use crate::progress::{CancellationToken, Progress, ProgressObserver, TerminalProgress};
+use crate::projection::{Projection, RealisticLens};
 use crate::ray::Ray;
+use crate::sampler::{IndependentSampler, SampleStream, Sampler};
+use crate::spectrum;
+use crate::transform::Transform;
 use crate::vec3::Vec3;
 
+#[derive(Clone)]
 pub struct Camera {
     aspect_ratio: f64,
     image_width: i32,
     samples_per_pixel: i32,
     max_depth: i32,
     background: Color,
+    spectral: bool,
+    packet_traversal: bool,
+    filter: Rc<dyn Filter>,
+    sampler: Rc<dyn Sampler>,
 
     vfov: f64,
     lookfrom: Point3,
     lookat: Point3,
     vup: Vec3,
+    projection: Option<Rc<dyn Projection>>,
+    lens: Option<RealisticLens>,
+    /// `lens` focused at the distance it is paired with, kept between renders
+    /// as focusing traces rays and searches for the exit pupil again.
+    focused_lens: Option<(f64, Rc<dyn Projection>)>,
 
     defocus_angle: f64,
     focus_dist: f64,
+    aperture: Rc<dyn Aperture>,
+    cat_eye: f64,
+    anamorphic: f64,
+
+    shutter_open: f64,
+    shutter_close: f64,
+    motion: Option<Keyframes<Transform>>,
+
+    crop: Option<(Range<i32>, Range<i32>)>,
+    crop_border: Option<Color>,
+    verbose: bool,
+    preview: Option<String>,
+    progress: Rc<dyn ProgressObserver>,
+    cancellation: Option<CancellationToken>,
+    rays: Cell<u64>,
 
     image_height: i32,
     center: Point3,
@@ -41,30 +78,143 @@ pub struct Camera {
 }
 
 impl Camera {
-    fn ray_color(&self, r: &Ray, depth: i32, world: &impl Hittable) -> Color {
+    /// The radiance along `r`. `traced` is the ray's closest hit when it has
+    /// already been traced as part of a packet.
+    fn ray_color<'w>(
+        &self,
+        r: &Ray,
+        depth: i32,
+        world: &'w impl Hittable,
+        traced: Option<Option<HitRecord<'w>>>,
+        media: &MediumStack,
+        sampler: &mut SampleStream,
+    ) -> Result<Color> {
+        let bounce = self.max_depth - depth;
         if depth <= 0 {
-            return Color::new(0.0, 0.0, 0.0);
+            self.trace(bounce, || "path cut off at max depth".to_string());
+            return Ok(Color::new(0.0, 0.0, 0.0));
         }
-
-        let mut rec: HitRecord = Default::default();
-        if !world.hit(r, Interval::new(0.001, f64::INFINITY), &mut rec) {
-            return self.background;
+        sampler.start_bounce(bounce as u32);
+        self.rays.set(self.rays.get() + 1);
+        let r = &r.with_alpha_sample(sampler.alpha_sample(bounce as u32));
+
+        let wavelength = r.wavelength();
+
+        let traced = traced.unwrap_or_else(|| {
+            let mut rec = HitRecord::default();
+            world
+                .hit(r, Interval::new(0.001, f64::INFINITY), &mut rec)
+                .then_some(rec)
+        });
+        let Some(mut rec) = traced else {
+            let color = media.transmittance(f64::INFINITY, wavelength)
+                * spectrum::project(self.background, wavelength);
+            self.trace(bounce, || format!("miss, background {}", rgb(color)));
+            return Ok(color);
+        };
+        rec.finish(r);
+        self.trace(bounce, || {
+            format!(
+                "hit t {:.5} at {} normal {} {} uv ({:.3}, {:.3})",
+                rec.t,
+                triple(rec.p),
+                triple(rec.normal),
+                if rec.front_face { "front" } else { "back" },
+                rec.u,
+                rec.v
+            )
+        });
+
+        let transmittance = media.transmittance(rec.t * r.direction().length(), wavelength);
+        let material = rec.material().ok_or(Error::MissingMaterial)?;
+        let medium = material.medium();
+
+        if let Some(medium) = medium {
+            if media.is_false_hit(&medium) {
+                self.trace(bounce, || "false hit, continuing".to_string());
+                let mut media = media.clone();
+                media.cross(medium, rec.front_face);
+                let continued =
+                    Ray::new(rec.p, r.direction(), r.time()).with_wavelength(wavelength);
+                return Ok(transmittance
+                    * self.ray_color(&continued, depth - 1, world, None, &media, sampler)?);
+            }
+            rec.outside_ior = media.outside_ior(&medium, rec.front_face, wavelength);
         }
 
         let mut scattered = Ray::default();
         let mut attenuation = Color::default();
-        let color_from_emission = rec.material.as_ref().unwrap().emitted(rec.u, rec.v, &rec.p);
-
-        if !rec
-            .material
-            .as_ref()
-            .unwrap()
-            .scatter(r, &rec, &mut attenuation, &mut scattered)
-        {
-            color_from_emission
+        let color_from_emission =
+            spectrum::project(material.emitted(rec.u, rec.v, &rec.p), wavelength);
+
+        if !material.scatter(r, &rec, &mut attenuation, &mut scattered, sampler) {
+            self.trace(bounce, || {
+                format!(
+                    "absorbed, emitted {} transmittance {}",
+                    rgb(color_from_emission),
+                    rgb(transmittance)
+                )
+            });
+            Ok(transmittance * color_from_emission)
+        } else {
+            let scattered = scattered.with_wavelength(wavelength);
+            let attenuation = spectrum::project(attenuation, wavelength);
+            self.trace(bounce, || {
+                format!(
+                    "scattered towards {} attenuation {} emitted {} transmittance {}",
+                    triple(scattered.direction()),
+                    rgb(attenuation),
+                    rgb(color_from_emission),
+                    rgb(transmittance)
+                )
+            });
+            let color_from_scatter = match medium {
+                Some(medium) if scattered.direction().dot(&rec.normal) < 0.0 => {
+                    let mut media = media.clone();
+                    media.cross(medium, rec.front_face);
+                    attenuation
+                        * self.ray_color(&scattered, depth - 1, world, None, &media, sampler)?
+                }
+                _ => {
+                    attenuation
+                        * self.ray_color(&scattered, depth - 1, world, None, media, sampler)?
+                }
+            };
+            Ok(transmittance * (color_from_emission + color_from_scatter))
+        }
+    }
+
+    /// Prints one step of a path when debugging a single pixel.
+    fn trace(&self, bounce: i32, message: impl FnOnce() -> String) {
+        if self.verbose {
+            println!(
+                "{:indent$}bounce {}: {}",
+                "",
+                bounce,
+                message(),
+                indent = 2 * bounce as usize + 2
+            );
+        }
+    }
+
+    fn sample_color<'w>(
+        &self,
+        r: &Ray,
+        world: &'w impl Hittable,
+        traced: Option<Option<HitRecord<'w>>>,
+        sampler: &mut SampleStream,
+    ) -> Result<Color> {
+        let media = MediumStack::default();
+        let u = sampler.get_1d();
+        if self.spectral {
+            let lambda = spectrum::sample_wavelength(u);
+            let r = r.with_wavelength(Some(lambda));
+            let radiance = self
+                .ray_color(&r, self.max_depth, world, traced, &media, sampler)?
+                .r();
+            Ok(spectrum::wavelength_to_rgb(lambda, radiance))
         } else {
-            let color_from_scatter = attenuation * self.ray_color(&scattered, depth - 1, world);
-            color_from_emission + color_from_scatter
+            self.ray_color(r, self.max_depth, world, traced, &media, sampler)
         }
     }
 }
@@ -77,12 +227,32 @@ impl Default for Camera {
             samples_per_pixel: 10,
             max_depth: 10,
             background: Color::new(0.0, 0.0, 0.0),
+            spectral: false,
+            packet_traversal: false,
+            filter: Rc::new(BoxFilter::default()),
+            sampler: Rc::new(IndependentSampler::new(0)),
             vfov: 90.0,
             lookfrom: Point3::new(0.0, 0.0, -1.0),
             lookat: Point3::new(0.0, 0.0, 0.0),
             vup: Vec3::new(0.0, 1.0, 0.0),
+            projection: None,
+            lens: None,
+            focused_lens: None,
             defocus_angle: 0.0,
             focus_dist: 10.0,
+            aperture: Rc::new(Circular),
+            cat_eye: 0.0,
+            anamorphic: 1.0,
+            shutter_open: 0.0,
+            shutter_close: 1.0,
+            motion: None,
+            crop: None,
+            crop_border: None,
+            verbose: false,
+            preview: None,
+            progress: Rc::new(TerminalProgress),
+            cancellation: None,
+            rays: Cell::new(0),
             image_height: 0,
             center: Default::default(),
             pixel_00_loc: Default::default(),
@@ -98,43 +268,58 @@ impl Default for Camera {
 }
 
 impl Camera {
-    pub(crate) fn aspect_ratio(mut self, aspect_ratio: f64) -> Self {
+    pub fn aspect_ratio(mut self, aspect_ratio: f64) -> Self {
         self.aspect_ratio = aspect_ratio;
         self
     }
 
-    pub(crate) fn image_width(mut self, width: i32) -> Self {
+    pub fn image_width(mut self, width: i32) -> Self {
         self.image_width = width;
         self
     }
-    pub(crate) fn samples_per_pixel(mut self, samples_per_pixel: i32) -> Self {
+    pub fn samples_per_pixel(mut self, samples_per_pixel: i32) -> Self {
         self.samples_per_pixel = samples_per_pixel;
         self
     }
-    pub(crate) fn max_depth(mut self, depth: i32) -> Self {
+    pub fn max_depth(mut self, depth: i32) -> Self {
         self.max_depth = depth;
         self
     }
 
-    pub(crate) fn vfov(mut self, vfov: f64) -> Self {
+    pub fn vfov(mut self, vfov: f64) -> Self {
         self.vfov = vfov;
         self
     }
 
-    pub(crate) fn lookfrom(mut self, lookfrom: Point3) -> Self {
+    pub fn lookfrom(mut self, lookfrom: Point3) -> Self {
         self.lookfrom = lookfrom;
         self
     }
 
-    pub(crate) fn lookat(mut self, lookat: Point3) -> Self {
+    pub fn lookat(mut self, lookat: Point3) -> Self {
         self.lookat = lookat;
         self
     }
-    pub(crate) fn vup(mut self, vup: Vec3) -> Self {
+    pub fn vup(mut self, vup: Vec3) -> Self {
         self.vup = vup;
         self
     }
 
+    /// Replaces the default perspective projection, which uses `vfov`.
+    pub fn projection(mut self, projection: Rc<dyn Projection>) -> Self {
+        self.projection = Some(projection);
+        self
+    }
+
+    /// Traces rays through a lens prescription, focused at `focus_dist`. The
+    /// lens replaces the projection and provides its own depth of field, so
+    /// leave `defocus_angle` at zero.
+    pub fn lens(mut self, lens: RealisticLens) -> Self {
+        self.lens = Some(lens);
+        self.focused_lens = None;
+        self
+    }
+
     pub fn defocus_angle(mut self, defocus_angle: f64) -> Self {
         self.defocus_angle = defocus_angle;
         self
@@ -145,47 +330,261 @@ impl Camera {
         self
     }
 
+    /// Trace a single sampled wavelength per path, so dispersive dielectrics split light.
+    pub fn spectral(mut self, spectral: bool) -> Self {
+        self.spectral = spectral;
+        self
+    }
+
+    /// Trace the camera rays of each pixel together as packets, which pays off
+    /// with a world that traverses packets, such as a
+    /// [`Bvh4`](crate::hittable::Bvh4). Off by default.
+    pub fn packet_traversal(mut self, packet_traversal: bool) -> Self {
+        self.packet_traversal = packet_traversal;
+        self
+    }
+
+    /// The pixel reconstruction filter; a one pixel wide box (radius 0.5) by default.
+    pub fn filter(mut self, filter: Rc<dyn Filter>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Where the random numbers come from; independent random samples by default.
+    pub fn sampler(mut self, sampler: Rc<dyn Sampler>) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
     pub fn focus_dist(mut self, focus_dist: f64) -> Self {
         self.focus_dist = focus_dist;
         self
     }
 
-    fn pixel_sample_square(&self) -> Vec3 {
-        let mut rng = rand::thread_rng();
-        let px = -0.5 + rng.gen::<f64>();
-        let py = -0.5 + rng.gen::<f64>();
+    pub fn aperture(mut self, aperture: Rc<dyn Aperture>) -> Self {
+        self.aperture = aperture;
+        self
+    }
+
+    /// Optical vignetting: off-axis the aperture is clipped by the lens barrel,
+    /// squeezing bokeh into cat's eyes towards the corners. Zero disables it.
+    pub fn cat_eye(mut self, cat_eye: f64) -> Self {
+        self.cat_eye = cat_eye;
+        self
+    }
+
+    /// Squeezes the aperture horizontally, so bokeh becomes `anamorphic` times
+    /// taller than it is wide.
+    pub fn anamorphic(mut self, anamorphic: f64) -> Self {
+        self.anamorphic = anamorphic;
+        self
+    }
+
+    /// The interval of scene time the exposure samples from.
+    pub fn shutter(mut self, open: f64, close: f64) -> Self {
+        self.shutter_open = open;
+        self.shutter_close = close;
+        self
+    }
+
+    /// Moves the whole camera over time; the transforms pivot around `lookfrom`.
+    pub fn motion(mut self, motion: Keyframes<Transform>) -> Self {
+        self.motion = Some(motion);
+        self
+    }
+
+    /// Only renders the pixels in columns `x` and rows `y`, writing an image of
+    /// just that region.
+    pub fn crop(mut self, x: Range<i32>, y: Range<i32>) -> Self {
+        self.crop = Some((x, y));
+        self
+    }
+
+    /// Writes the cropped region into the full size image instead, with every
+    /// pixel outside of it set to `border`.
+    pub fn crop_border(mut self, border: Color) -> Self {
+        self.crop_border = Some(border);
+        self
+    }
+
+    fn pixel_sample_offset(&self, (px, py): (f64, f64)) -> Vec3 {
         (px * self.pixel_delta_u) + (py * self.pixel_delta_v)
     }
 
-    fn defocus_disk_sample(&self) -> Point3 {
-        let p = Vec3::random_in_unit_disk();
+    /// A point on the aperture, seen from image position `(s, t)` in `[0, 1]`.
+    fn aperture_sample(&self, s: f64, t: f64, u: (f64, f64), sampler: &SampleStream) -> (f64, f64) {
+        const MAX_TRIES: u32 = 64;
+
+        let aspect_ratio = self.image_width as f64 / self.image_height as f64;
+        let scale = self.cat_eye / (aspect_ratio * aspect_ratio + 1.0).sqrt();
+        let shift = (
+            (2.0 * s - 1.0) * aspect_ratio * scale,
+            (1.0 - 2.0 * t) * scale,
+        );
+
+        // Rejected tries go on with the sampler's points for retries.
+        let mut p = (shift.0 / 2.0, shift.1 / 2.0);
+        for k in 0..MAX_TRIES {
+            let u = if k == 0 { u } else { sampler.retry_2d(k - 1) };
+            let (x, y) = self.aperture.sample(u);
+            let (dx, dy) = (x + shift.0, y + shift.1);
+            if dx * dx + dy * dy <= 1.0 {
+                p = (x, y);
+                break;
+            }
+        }
+        (p.0 / self.anamorphic, p.1)
+    }
+
+    fn defocus_offset(&self, s: f64, t: f64, u: (f64, f64), sampler: &SampleStream) -> Vec3 {
+        let (x, y) = self.aperture_sample(s, t, u, sampler);
 
-        self.center + &(p.x() * self.defocus_disk_u) + &(p.y() * self.defocus_disk_v)
+        (x * self.defocus_disk_u) + (y * self.defocus_disk_v)
     }
 
-    fn get_ray(&mut self, i: i32, j: i32) -> Ray {
-        let pixel_center =
-            self.pixel_00_loc + &(i as f64 * self.pixel_delta_u) + &(j as f64 * self.pixel_delta_v);
-        let pixel_sample = pixel_center + &self.pixel_sample_square();
+    fn defocus_disk_sample(&self, s: f64, t: f64, u: (f64, f64), sampler: &SampleStream) -> Point3 {
+        self.center + self.defocus_offset(s, t, u, sampler)
+    }
 
-        let ray_origin = if self.defocus_angle <= 0.0 {
-            self.center
-        } else {
-            self.defocus_disk_sample()
+    /// A ray through pixel `(i, j)` from the configured projection, in world space.
+    fn projected_ray(
+        &self,
+        projection: &dyn Projection,
+        i: i32,
+        j: i32,
+        (px, py): (f64, f64),
+        lens: (f64, f64),
+        sampler: &SampleStream,
+    ) -> Option<(Point3, Vec3)> {
+        let s = (i as f64 + 0.5 + px) / self.image_width as f64;
+        let t = (j as f64 + 0.5 + py) / self.image_height as f64;
+        let aspect_ratio = self.image_width as f64 / self.image_height as f64;
+        let (origin, direction) = projection.ray(s, t, aspect_ratio, lens)?;
+
+        let to_world = |v: Vec3| v.x() * self.u + v.y() * self.v + v.z() * self.w;
+        let origin = self.center + to_world(origin.into());
+        let direction = to_world(direction);
+        if self.defocus_angle <= 0.0 {
+            return Some((origin, direction));
+        }
+
+        let focus = origin + self.focus_dist * direction;
+        let origin = origin + self.defocus_offset(s, t, lens, sampler);
+        Some((origin, focus - origin))
+    }
+
+    /// A ray through pixel `(i, j)`, `offset` pixels away from its centre.
+    fn get_ray(
+        &self,
+        i: i32,
+        j: i32,
+        offset: (f64, f64),
+        sampler: &mut SampleStream,
+    ) -> Option<Ray> {
+        let lens = sampler.get_2d();
+        let ray_time =
+            self.shutter_open + sampler.get_1d() * (self.shutter_close - self.shutter_open);
+
+        let (ray_origin, ray_direction) = match &self.projection {
+            Some(projection) => {
+                self.projected_ray(projection.as_ref(), i, j, offset, lens, sampler)?
+            }
+            None => {
+                let pixel_center = self.pixel_00_loc
+                    + i as f64 * self.pixel_delta_u
+                    + j as f64 * self.pixel_delta_v;
+                let pixel_sample = pixel_center + self.pixel_sample_offset(offset);
+
+                let ray_origin = if self.defocus_angle <= 0.0 {
+                    self.center
+                } else {
+                    let s = (i as f64 + 0.5) / self.image_width as f64;
+                    let t = (j as f64 + 0.5) / self.image_height as f64;
+                    self.defocus_disk_sample(s, t, lens, sampler)
+                };
+                (ray_origin, pixel_sample - ray_origin)
+            }
         };
-        let ray_direction = pixel_sample - ray_origin;
-        let mut rng = rand::thread_rng();
-        let ray_time = rng.gen();
+        Some(match &self.motion {
+            None => Ray::new(ray_origin, ray_direction, ray_time),
+            Some(motion) => {
+                let transform = motion.at(ray_time);
+                let pivot = Vec3::from(self.center);
+                let origin = transform.apply_point(&(ray_origin - pivot)) + pivot;
+                Ray::new(origin, transform.apply_vector(&ray_direction), ray_time)
+            }
+        })
+    }
 
-        Ray::new(ray_origin, ray_direction, ray_time)
+    /// Fails on settings which cannot produce an image.
+    fn validate(&self) -> Result<()> {
+        if self.image_width < 1 {
+            return Err(Error::invalid(format!(
+                "image width must be at least 1, got {}",
+                self.image_width
+            )));
+        }
+        if self.samples_per_pixel < 1 {
+            return Err(Error::invalid(format!(
+                "samples per pixel must be at least 1, got {}",
+                self.samples_per_pixel
+            )));
+        }
+        if self.max_depth < 0 {
+            return Err(Error::invalid(format!(
+                "max depth must not be negative, got {}",
+                self.max_depth
+            )));
+        }
+        Error::check_positive("aspect ratio", self.aspect_ratio)?;
+        Error::check_positive("focus distance", self.focus_dist)?;
+        Error::check_positive("anamorphic squeeze", self.anamorphic)?;
+        if !(self.vfov > 0.0 && self.vfov < 180.0) {
+            return Err(Error::invalid(format!(
+                "vertical field of view must be between 0 and 180 degrees, got {}",
+                self.vfov
+            )));
+        }
+        if !(0.0..180.0).contains(&self.defocus_angle) {
+            return Err(Error::invalid(format!(
+                "defocus angle must be between 0 and 180 degrees, got {}",
+                self.defocus_angle
+            )));
+        }
+        if self.shutter_close < self.shutter_open {
+            return Err(Error::invalid(format!(
+                "shutter closes at {} before it opens at {}",
+                self.shutter_close, self.shutter_open
+            )));
+        }
+        if self.motion.as_ref().is_some_and(|motion| motion.is_empty()) {
+            return Err(Error::invalid("camera motion needs at least one key"));
+        }
+        if self.vup.cross(&(self.lookfrom - self.lookat)).near_zero() {
+            return Err(Error::invalid(
+                "lookfrom and lookat must differ, along a direction not parallel to vup",
+            ));
+        }
+        Ok(())
     }
 
-    fn initialize(&mut self) {
+    fn initialize(&mut self) -> Result<()> {
+        self.validate()?;
+
         self.image_height = (self.image_width as f64 / self.aspect_ratio) as i32;
         self.image_height = max(self.image_height, 1);
 
         self.center = self.lookfrom;
 
+        if let Some(lens) = &self.lens {
+            let focused = match &self.focused_lens {
+                Some((focus_dist, focused)) if *focus_dist == self.focus_dist => focused.clone(),
+                _ => Rc::new(lens.clone().focused(self.focus_dist)),
+            };
+            self.focused_lens = Some((self.focus_dist, focused.clone()));
+            self.projection = Some(focused);
+        }
+
         let theta = self.vfov.to_radians();
         let h = (theta / 2.0).tan();
         let viewport_height = 2.0 * h * self.focus_dist;
@@ -203,47 +602,304 @@ impl Camera {
 
         let viewport_upper_left =
             self.center - (self.focus_dist * self.w) - viewport_u / 2.0 - viewport_v / 2.0;
-        self.pixel_00_loc = &viewport_upper_left + 0.5 * (self.pixel_delta_u + self.pixel_delta_v);
+        self.pixel_00_loc = viewport_upper_left + 0.5 * (self.pixel_delta_u + self.pixel_delta_v);
 
         let defocus_radius = self.focus_dist * (self.defocus_angle / 2.0).to_radians().tan();
         self.defocus_disk_u = self.u * defocus_radius;
         self.defocus_disk_v = self.v * defocus_radius;
+        Ok(())
+    }
+
+    /// The rendered columns and rows, clamped to the image.
+    fn crop_window(&self) -> (Range<i32>, Range<i32>) {
+        match &self.crop {
+            Some((x, y)) => (
+                x.start.max(0)..x.end.min(self.image_width),
+                y.start.max(0)..y.end.min(self.image_height),
+            ),
+            None => (0..self.image_width, 0..self.image_height),
+        }
+    }
+
+    /// Starts sample `s` of pixel `(i, j)`: its sample stream, its filter
+    /// offset and weight, and its camera ray unless the projection has none.
+    fn camera_sample(
+        &self,
+        i: i32,
+        j: i32,
+        s: i32,
+        filter: &FilterSampler,
+    ) -> (SampleStream<'_>, (f64, f64, f64), Option<Ray>) {
+        let mut sampler = SampleStream::new(
+            self.sampler.as_ref(),
+            (i, j),
+            s as u32,
+            self.samples_per_pixel as u32,
+        );
+        let (dx, dy, weight) = filter.sample(sampler.get_2d());
+        // Camera rays traced ahead in packets need the sample for their
+        // bounce already.
+        let r = self
+            .get_ray(i, j, (dx, dy), &mut sampler)
+            .map(|r| r.with_alpha_sample(sampler.alpha_sample(0)));
+        if r.is_none() && self.verbose {
+            println!("sample {}: no ray", s);
+        }
+        (sampler, (dx, dy, weight), r)
+    }
+
+    /// The radiance of sample `s` along camera ray `r`.
+    fn shade_sample<'w>(
+        &self,
+        s: i32,
+        (dx, dy, weight): (f64, f64, f64),
+        r: &Ray,
+        world: &'w impl Hittable,
+        traced: Option<Option<HitRecord<'w>>>,
+        sampler: &mut SampleStream,
+    ) -> Result<Color> {
+        if self.verbose {
+            println!(
+                "sample {}: offset ({:.3}, {:.3}) weight {:.3}, ray from {} towards {} at time {:.3}",
+                s,
+                dx,
+                dy,
+                weight,
+                triple(r.origin()),
+                triple(r.direction()),
+                r.time()
+            );
+        }
+        let color = self.sample_color(r, world, traced, sampler)?;
+        if self.verbose {
+            println!("  radiance {}", rgb(color));
+        }
+        Ok(color)
+    }
+
+    fn pixel_color(
+        &self,
+        i: i32,
+        j: i32,
+        filter: &FilterSampler,
+        exposure: f64,
+        world: &impl Hittable,
+    ) -> Result<Color> {
+        let mut pixel_color = Color::new(0.0, 0.0, 0.0);
+        let mut weight_sum = 0.0;
+        if self.packet_traversal && self.max_depth > 0 {
+            let mut rays = [Ray::default(); PACKET_SIZE];
+            let mut recs = [HitRecord::default(); PACKET_SIZE];
+            let mut samples: [Option<PendingSample>; PACKET_SIZE] = [const { None }; PACKET_SIZE];
+            for first in (0..self.samples_per_pixel).step_by(PACKET_SIZE) {
+                let last = (first + PACKET_SIZE as i32).min(self.samples_per_pixel);
+                let mut count = 0;
+                for s in first..last {
+                    let (sampler, offset, r) = self.camera_sample(i, j, s, filter);
+                    weight_sum += offset.2;
+                    if let Some(r) = r {
+                        rays[count] = r;
+                        samples[count] = Some((s, offset, sampler));
+                        count += 1;
+                    }
+                }
+
+                // The camera rays are traced together before any of them is
+                // shaded.
+                let t = Interval::new(0.001, f64::INFINITY);
+                let hits = world.hit_packet(&rays[..count], t, &mut recs[..count]);
+                for k in 0..count {
+                    let Some((s, offset, mut sampler)) = samples[k].take() else {
+                        continue;
+                    };
+                    let traced = Some((hits >> k & 1 != 0).then_some(recs[k]));
+                    let color =
+                        self.shade_sample(s, offset, &rays[k], world, traced, &mut sampler)?;
+                    pixel_color += offset.2 * exposure * color;
+                }
+            }
+        } else {
+            for s in 0..self.samples_per_pixel {
+                let (mut sampler, offset, r) = self.camera_sample(i, j, s, filter);
+                weight_sum += offset.2;
+                let Some(r) = r else {
+                    continue;
+                };
+                let color = self.shade_sample(s, offset, &r, world, None, &mut sampler)?;
+                pixel_color += offset.2 * exposure * color;
+            }
+        }
+        if weight_sum > 0.0 {
+            pixel_color = (1.0 / weight_sum) * pixel_color;
+        }
+        Ok(pixel_color)
+    }
+
+    /// Serves the image in progress over HTTP on `address`, such as
+    /// `127.0.0.1:8080`, for watching long renders from a browser. The
+    /// address must be a loopback one.
+    pub fn preview(mut self, address: &str) -> Self {
+        self.preview = Some(address.to_string());
+        self
+    }
+
+    /// Where progress is reported; a status line on the terminal by default.
+    pub fn progress(mut self, progress: Rc<dyn ProgressObserver>) -> Self {
+        self.progress = progress;
+        self
     }
-    pub fn render(&mut self, file_name: &str, world: &impl Hittable) -> std::io::Result<()> {
-        self.initialize();
+
+    /// Lets the render be stopped early through `token`. A cancelled render
+    /// still writes a complete image file, black where nothing was rendered.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    pub(crate) fn observer(&self) -> &dyn ProgressObserver {
+        self.progress.as_ref()
+    }
+
+    pub(crate) fn sample_count(&self) -> i32 {
+        self.samples_per_pixel
+    }
+
+    /// The size of the rendered image in pixels, before any cropping.
+    pub fn image_size(&mut self) -> Result<(i32, i32)> {
+        self.initialize()?;
+        Ok((self.image_width, self.image_height))
+    }
+
+    /// Renders the pixels in columns `x` and rows `y` row by row, as linear
+    /// colours without gamma.
+    pub fn render_tile(
+        &mut self,
+        x: Range<i32>,
+        y: Range<i32>,
+        world: &impl Hittable,
+    ) -> Result<Vec<Color>> {
+        self.initialize()?;
+
+        let exposure = self.projection.as_ref().map_or(1.0, |p| p.exposure());
+        let filter = FilterSampler::new(self.filter.as_ref());
+
+        let mut pixels = Vec::with_capacity(x.len() * y.len());
+        for j in y {
+            for i in x.clone() {
+                pixels.push(self.pixel_color(i, j, &filter, exposure, world)?);
+            }
+        }
+        Ok(pixels)
+    }
+
+    /// Renders the single pixel `(i, j)`, printing every step of every path
+    /// traced through it, and returns its linear colour.
+    pub fn debug_pixel(&mut self, i: i32, j: i32, world: &impl Hittable) -> Result<Color> {
+        self.initialize()?;
+
+        let exposure = self.projection.as_ref().map_or(1.0, |p| p.exposure());
+        let filter = FilterSampler::new(self.filter.as_ref());
+
+        println!("pixel ({}, {}), {} samples", i, j, self.samples_per_pixel);
+        self.verbose = true;
+        let color = self.pixel_color(i, j, &filter, exposure, world);
+        self.verbose = false;
+        let color = color?;
+        println!("pixel ({}, {}) = {}", i, j, rgb(color));
+
+        Ok(color)
+    }
+
+    pub fn render(&mut self, file_name: &str, world: &impl Hittable) -> Result<()> {
+        self.initialize()?;
+
+        let (columns, rows) = self.crop_window();
+        let (output_columns, output_rows) = match self.crop_border {
+            Some(_) => (0..self.image_width, 0..self.image_height),
+            None => (columns.clone(), rows.clone()),
+        };
+
+        let preview = match &self.preview {
+            Some(address) => Some(PreviewServer::start(
+                address.as_str(),
+                output_columns.len() as u32,
+                output_rows.len() as u32,
+                self.samples_per_pixel,
+            )?),
+            None => None,
+        };
 
         let mut file = File::create(file_name)?;
 
         writeln!(file, "P3")?;
-        writeln!(file, "{}", self.image_width)?;
-        writeln!(file, "{}", self.image_height)?;
+        writeln!(file, "{}", output_columns.len())?;
+        writeln!(file, "{}", output_rows.len())?;
         writeln!(file, "{}", 255)?;
+        if let Some(preview) = &preview {
+            self.progress
+                .message(&format!("Preview at http://{}/", preview.address()));
+        }
 
         let start = Instant::now();
-
-        for j in 0..self.image_height {
-            print!(
-                "\rScanline {}/{} ({:.2}%), {} remaining. Time Spend: {:.2}s. Time Remaining Estimate: {:.2}s     ",
-                j + 1,
-                self.image_height,
-                ((j + 1) as f64 / self.image_height as f64) * 100.0,
-                (self.image_height - j),
-                start.elapsed().as_secs_f64(),
-                (start.elapsed().as_secs_f64() / max(j, 1) as f64) * (self.image_height - j) as f64,
-            );
-            stdout().flush().unwrap();
-            for i in 0..self.image_width {
-                let mut pixel_color = Color::new(0.0, 0.0, 0.0);
-                for _ in 0..self.samples_per_pixel {
-                    let r = self.get_ray(i, j);
-                    pixel_color += self.ray_color(&r, self.max_depth, world);
+        let exposure = self.projection.as_ref().map_or(1.0, |p| p.exposure());
+        let filter = FilterSampler::new(self.filter.as_ref());
+        self.rays.set(0);
+
+        let mut progress = Progress {
+            unit: "scanline",
+            completed: 0,
+            total: output_rows.len() as u32,
+            samples: 0,
+            rays: Some(0),
+            elapsed: Duration::ZERO,
+        };
+        let cancelled = || self.cancellation.as_ref().is_some_and(|c| c.is_cancelled());
+        for (row, j) in output_rows.enumerate() {
+            for i in output_columns.clone() {
+                // Once cancelled, the rest of the image is left black.
+                let pixel_color = if cancelled() {
+                    Color::default()
+                } else if columns.contains(&i) && rows.contains(&j) {
+                    progress.samples += self.samples_per_pixel as u64;
+                    self.pixel_color(i, j, &filter, exposure, world)?.gamma()
+                } else {
+                    self.crop_border.unwrap_or_default()
+                };
+                writeln!(file, "{}", pixel_color)?;
+                if let Some(preview) = &preview {
+                    preview.set_pixel((i - output_columns.start) as u32, row as u32, pixel_color);
                 }
-                pixel_color.sample_scale(self.samples_per_pixel);
-                writeln!(file, "{}", pixel_color.gamma())?;
             }
+            if cancelled() {
+                continue;
+            }
+            if let Some(preview) = &preview {
+                preview.row_done();
+            }
+            progress.completed += 1;
+            progress.rays = Some(self.rays.get());
+            progress.elapsed = start.elapsed();
+            self.progress.update(&progress);
         }
-        println!("\r...done. Total Time: {}s                                                                                                         ", start.elapsed().as_secs_f64());
+        if let Some(preview) = &preview {
+            preview.finish();
+        }
+        progress.elapsed = start.elapsed();
+        self.progress.finished(&progress, cancelled());
 
         Ok(())
     }
 }
+
+/// A camera sample waiting for its packet to be traced: its index, filter
+/// offset and weight, and sample stream.
+type PendingSample<'a> = (i32, (f64, f64, f64), SampleStream<'a>);
+
+fn triple(v: impl Into<Vec3>) -> String {
+    let v = v.into();
+    format!("({:.4}, {:.4}, {:.4})", v.x(), v.y(), v.z())
+}
+
+fn rgb(c: Color) -> String {
+    format!("({:.4}, {:.4}, {:.4})", c.r(), c.g(), c.b())
+}
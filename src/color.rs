@@ -1,60 +1,55 @@
 use crate::interval::Interval;
+use crate::simd::{lanes_op, F3};
 use image::Rgb;
 use rand::Rng;
 use std::fmt::{Display, Formatter};
-use std::ops;
-use std::ops::Mul;
-
-#[derive(Copy, Clone)]
-pub struct Color {
-    r: f64,
-    g: f64,
-    b: f64,
-}
 
-impl Default for Color {
-    fn default() -> Self {
-        Self {
-            r: 0.0,
-            g: 0.0,
-            b: 0.0,
-        }
-    }
-}
+#[derive(Copy, Clone, Default)]
+pub struct Color(F3);
 
 impl Color {
-    pub(crate) fn new(r: f64, g: f64, b: f64) -> Self {
-        Self { r, g, b }
+    #[inline]
+    pub fn new(r: f64, g: f64, b: f64) -> Self {
+        Self(F3::new(r, g, b))
+    }
+
+    #[inline]
+    pub(crate) fn from_lanes(lanes: F3) -> Self {
+        Self(lanes)
+    }
+
+    #[inline]
+    pub(crate) fn lanes(&self) -> F3 {
+        self.0
     }
 
     pub fn random() -> Self {
         let mut rng = rand::thread_rng();
-        Self {
-            r: rng.gen::<f64>(),
-            g: rng.gen::<f64>(),
-            b: rng.gen::<f64>(),
-        }
+        Self::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>())
     }
 
     pub fn random_range(min: f64, max: f64) -> Self {
         let mut rng = rand::thread_rng();
-        Self {
-            r: rng.gen_range(min..max),
-            g: rng.gen_range(min..max),
-            b: rng.gen_range(min..max),
-        }
+        Self::new(
+            rng.gen_range(min..max),
+            rng.gen_range(min..max),
+            rng.gen_range(min..max),
+        )
     }
 }
 
 impl Color {
-    pub(crate) fn r(&self) -> f64 {
-        self.r
+    #[inline]
+    pub fn r(&self) -> f64 {
+        self.0.x()
     }
-    pub(crate) fn g(&self) -> f64 {
-        self.g
+    #[inline]
+    pub fn g(&self) -> f64 {
+        self.0.y()
     }
-    pub(crate) fn b(&self) -> f64 {
-        self.b
+    #[inline]
+    pub fn b(&self) -> f64 {
+        self.0.z()
     }
 
     fn channel_to_u8(channel: f64) -> u8 {
@@ -63,28 +58,27 @@ impl Color {
     }
 
     pub fn r_as_u8(&self) -> u8 {
-        Self::channel_to_u8(self.r)
+        Self::channel_to_u8(self.r())
     }
     pub fn g_as_u8(&self) -> u8 {
-        Self::channel_to_u8(self.g)
+        Self::channel_to_u8(self.g())
     }
     pub fn b_as_u8(&self) -> u8 {
-        Self::channel_to_u8(self.b)
+        Self::channel_to_u8(self.b())
+    }
+
+    #[inline]
+    pub fn luminance(&self) -> f64 {
+        self.0.dot(F3::new(0.2126, 0.7152, 0.0722))
     }
 
     pub fn sample_scale(&mut self, samples: i32) {
-        let scale = 1.0 / samples as f64;
-        self.r *= scale;
-        self.g *= scale;
-        self.b *= scale;
+        self.0 = self.0 * F3::scalar(1.0 / samples as f64);
     }
 
+    #[inline]
     pub fn gamma(&self) -> Self {
-        Self {
-            r: self.r.sqrt(),
-            g: self.g.sqrt(),
-            b: self.b.sqrt(),
-        }
+        Self(self.0.sqrt())
     }
 }
 
@@ -114,58 +108,18 @@ impl Into<Rgb<u8>> for Color {
     }
 }
 
-impl ops::Mul<Color> for f64 {
-    type Output = Color;
-
-    fn mul(self, rhs: Color) -> Self::Output {
-        Self::Output::new(self * rhs.r(), self * rhs.g(), self * rhs.b())
-    }
-}
-
-impl ops::Mul<&Color> for f64 {
-    type Output = Color;
-
-    fn mul(self, rhs: &Color) -> Self::Output {
-        Self::Output::new(self * rhs.r(), self * rhs.g(), self * rhs.b())
-    }
-}
-
-impl ops::Mul<f64> for Color {
-    type Output = Color;
-
-    fn mul(self, rhs: f64) -> Self::Output {
-        Self::Output::new(self.r * rhs, self.g * rhs, self.b * rhs)
-    }
-}
-
-impl ops::Add for Color {
-    type Output = Color;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::Output {
-            r: self.r + rhs.r,
-            g: self.g + rhs.g,
-            b: self.b + rhs.b,
-        }
-    }
-}
-
-impl ops::AddAssign for Color {
+lanes_op!(Mul, mul, f64, Color, Color, |a, b| Color(
+    F3::scalar(a) * b.0
+));
+lanes_op!(Mul, mul, Color, f64, Color, |a, b| Color(
+    a.0 * F3::scalar(b)
+));
+lanes_op!(Add, add, Color, Color, Color, |a, b| Color(a.0 + b.0));
+lanes_op!(Mul, mul, Color, Color, Color, |a, b| Color(a.0 * b.0));
+
+impl std::ops::AddAssign for Color {
+    #[inline]
     fn add_assign(&mut self, rhs: Self) {
-        self.r += rhs.r;
-        self.g += rhs.g;
-        self.b += rhs.b;
-    }
-}
-
-impl Mul for Color {
-    type Output = Color;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        Self::Output {
-            r: self.r * rhs.r,
-            g: self.g * rhs.g,
-            b: self.b * rhs.b,
-        }
+        self.0 = self.0 + rhs.0;
     }
 }
@@ -0,0 +1,324 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::color::Color;
+use crate::distributed::protocol::{Message, Tile};
+use crate::distributed::SceneBuilder;
+use crate::error::{Error, Result};
+use crate::progress::{CancellationToken, Progress, ProgressObserver, TerminalProgress};
+
+/// How often idle threads look for new connections or reassigned tiles.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Splits an image into tiles and hands them out to workers over TCP.
+///
+/// A worker which disconnects, or takes longer than the timeout over a tile,
+/// is dropped and its tile given to the next worker asking for one. With no
+/// worker connected for as long as the idle timeout, the render fails.
+pub struct Coordinator {
+    scene: String,
+    seed: u64,
+    tile_size: i32,
+    timeout: Duration,
+    idle_timeout: Duration,
+    progress: Rc<dyn ProgressObserver>,
+    cancellation: Option<CancellationToken>,
+}
+
+/// The tiles still waiting for a worker, whether the image is finished, the
+/// connection to every worker and how many of them are still being served.
+struct Queue {
+    pending: Mutex<VecDeque<Tile>>,
+    finished: AtomicBool,
+    connections: Mutex<Vec<TcpStream>>,
+    workers: AtomicUsize,
+}
+
+impl Coordinator {
+    pub fn new(scene: &str, seed: u64) -> Self {
+        Self {
+            scene: scene.to_string(),
+            seed,
+            tile_size: 32,
+            timeout: Duration::from_secs(600),
+            idle_timeout: Duration::from_secs(600),
+            progress: Rc::new(TerminalProgress),
+            cancellation: None,
+        }
+    }
+
+    pub fn tile_size(mut self, tile_size: i32) -> Self {
+        self.tile_size = tile_size.max(1);
+        self
+    }
+
+    /// How long a worker may spend on one tile before it is presumed dead.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How long to wait with no worker connected, from the start or since the
+    /// last one was lost, before giving up on the render.
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    pub fn progress(mut self, progress: Rc<dyn ProgressObserver>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Stops handing out tiles once `token` is cancelled; tiles not yet
+    /// returned are left black.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Accepts workers on `listener`, and writes the assembled image to
+    /// `file_name` once every tile has come back. Fails without writing it
+    /// when no worker is connected for the idle timeout. Binding the listener is left
+    /// to the caller, which can then bind port 0 and learn the port from
+    /// [`TcpListener::local_addr`] before telling workers where to connect.
+    pub fn render(
+        &self,
+        listener: TcpListener,
+        scenes: SceneBuilder,
+        file_name: &str,
+    ) -> Result<()> {
+        let (mut camera, _) = scenes(&self.scene, self.seed)
+            .ok_or_else(|| Error::invalid(format!("unknown scene {}", self.scene)))?;
+        let (width, height) = camera.image_size()?;
+
+        let tiles = self.tiles(width, height);
+        let total = tiles.len();
+        let queue = Arc::new(Queue {
+            pending: Mutex::new(tiles.into()),
+            finished: AtomicBool::new(false),
+            connections: Mutex::new(Vec::new()),
+            workers: AtomicUsize::new(0),
+        });
+
+        listener.set_nonblocking(true)?;
+        self.progress.message(&format!(
+            "Waiting for workers on {} to render {} tiles",
+            listener.local_addr()?,
+            total
+        ));
+
+        let (results, received) = mpsc::channel();
+        let job = (self.scene.clone(), self.seed, self.timeout);
+        let acceptor = {
+            let queue = queue.clone();
+            thread::spawn(move || accept(listener, job, queue, results))
+        };
+
+        let mut progress = Progress {
+            unit: "tile",
+            completed: 0,
+            total: total as u32,
+            samples: 0,
+            rays: None,
+            elapsed: Duration::ZERO,
+        };
+        let samples_per_pixel = camera.sample_count() as u64;
+        let cancelled = || self.cancellation.as_ref().is_some_and(|c| c.is_cancelled());
+
+        let start = Instant::now();
+        let mut image = vec![Color::default(); (width * height) as usize];
+        let mut done = vec![false; total];
+        let mut idle_since = Instant::now();
+        let mut idle = false;
+        while (progress.completed as usize) < total && !cancelled() {
+            if queue.workers.load(Ordering::SeqCst) > 0 {
+                idle_since = Instant::now();
+            } else if idle_since.elapsed() >= self.idle_timeout {
+                idle = true;
+                break;
+            }
+            let (tile, pixels) = match received.recv_timeout(POLL_INTERVAL) {
+                Ok(Event::Pixels(tile, pixels)) => (tile, pixels),
+                Ok(Event::Message(message)) => {
+                    self.progress.message(&message);
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if std::mem::replace(&mut done[tile.id as usize], true) {
+                continue;
+            }
+            let mut pixels = pixels.into_iter();
+            for j in tile.y.clone() {
+                for i in tile.x.clone() {
+                    let [r, g, b] = pixels.next().unwrap_or_default();
+                    image[(j * width + i) as usize] = Color::new(r as f64, g as f64, b as f64);
+                }
+            }
+            progress.completed += 1;
+            progress.samples += tile.len() as u64 * samples_per_pixel;
+            progress.elapsed = start.elapsed();
+            self.progress.update(&progress);
+        }
+
+        queue.finished.store(true, Ordering::SeqCst);
+        if cancelled() {
+            // Workers still rendering a tile would hold up the join below for
+            // as long as the timeout, waiting for pixels nobody wants.
+            for stream in queue.connections.lock().unwrap().iter() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+        if let Ok(Err(e)) = acceptor.join() {
+            self.progress
+                .message(&format!("Failed to accept workers: {}", e));
+        }
+        for event in received.try_iter() {
+            if let Event::Message(message) = event {
+                self.progress.message(&message);
+            }
+        }
+        progress.elapsed = start.elapsed();
+        self.progress.finished(&progress, cancelled() || idle);
+        if idle {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "no worker connected for {} s with {} tiles left",
+                    self.idle_timeout.as_secs_f64(),
+                    total - progress.completed as usize
+                ),
+            )
+            .into());
+        }
+
+        let mut file = BufWriter::new(File::create(file_name)?);
+        writeln!(file, "P3")?;
+        writeln!(file, "{}", width)?;
+        writeln!(file, "{}", height)?;
+        writeln!(file, "{}", 255)?;
+        for pixel in image {
+            writeln!(file, "{}", pixel.gamma())?;
+        }
+        file.flush()?;
+        Ok(())
+    }
+
+    fn tiles(&self, width: i32, height: i32) -> Vec<Tile> {
+        let mut tiles = Vec::new();
+        for y in (0..height).step_by(self.tile_size as usize) {
+            for x in (0..width).step_by(self.tile_size as usize) {
+                tiles.push(Tile {
+                    id: tiles.len() as u32,
+                    x: x..(x + self.tile_size).min(width),
+                    y: y..(y + self.tile_size).min(height),
+                });
+            }
+        }
+        tiles
+    }
+}
+
+/// What the threads serving workers report back to the coordinator, whose
+/// observer they cannot reach themselves.
+enum Event {
+    Pixels(Tile, Vec<[f32; 3]>),
+    Message(String),
+}
+
+type Results = Sender<Event>;
+
+/// Starts a thread for every worker that connects, until the image is done.
+fn accept(
+    listener: TcpListener,
+    job: (String, u64, Duration),
+    queue: Arc<Queue>,
+    results: Results,
+) -> io::Result<()> {
+    let mut workers = Vec::new();
+    while !queue.finished.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let (job, queue, results) = (job.clone(), queue.clone(), results.clone());
+                queue.workers.fetch_add(1, Ordering::SeqCst);
+                workers.push(thread::spawn(move || {
+                    serve(stream, job, queue.clone(), results);
+                    queue.workers.fetch_sub(1, Ordering::SeqCst);
+                }));
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(e),
+        }
+    }
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+/// Feeds tiles to one worker until it fails or the image is done. A tile the
+/// worker does not return goes back on the queue.
+fn serve(
+    stream: TcpStream,
+    (scene, seed, timeout): (String, u64, Duration),
+    queue: Arc<Queue>,
+    results: Results,
+) {
+    let Ok(peer) = stream.peer_addr() else {
+        return;
+    };
+    let _ = results.send(Event::Message(format!("Worker {} connected", peer)));
+
+    let setup = || -> io::Result<_> {
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(timeout))?;
+        queue.connections.lock().unwrap().push(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream.try_clone()?);
+        Message::Job { scene, seed }.write(&mut writer)?;
+        Ok((BufReader::new(stream.try_clone()?), writer))
+    };
+    let Ok((mut reader, mut writer)) = setup() else {
+        let _ = results.send(Event::Message(format!("Worker {} lost", peer)));
+        return;
+    };
+
+    loop {
+        if queue.finished.load(Ordering::SeqCst) {
+            let _ = Message::Done.write(&mut writer);
+            return;
+        }
+        let Some(tile) = queue.pending.lock().unwrap().pop_front() else {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        };
+
+        let reply = Message::Tile(tile.clone())
+            .write(&mut writer)
+            .and_then(|_| Message::read(&mut reader, tile.len()));
+        match reply {
+            Ok(Message::Pixels { id, pixels }) if id == tile.id && pixels.len() == tile.len() => {
+                let _ = results.send(Event::Pixels(tile, pixels));
+            }
+            _ if queue.finished.load(Ordering::SeqCst) => return,
+            _ => {
+                let _ = results.send(Event::Message(format!(
+                    "Worker {} lost, reassigning tile {}",
+                    peer, tile.id
+                )));
+                queue.pending.lock().unwrap().push_back(tile);
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+mod coordinator;
+mod protocol;
+mod worker;
+
+use crate::camera::Camera;
+use crate::hittable::HittableList;
+
+pub use coordinator::Coordinator;
+pub use worker::run_worker;
+
+/// Builds a scene by name. Every process taking part in a render must build
+/// exactly the same scene, and sample it the same way, for a name and seed.
+pub type SceneBuilder = fn(&str, u64) -> Option<(Camera, HittableList)>;
@@ -0,0 +1,167 @@
+use std::io::{self, Read, Write};
+use std::ops::Range;
+
+const JOB: u8 = 0;
+const TILE: u8 = 1;
+const PIXELS: u8 = 2;
+const DONE: u8 = 3;
+
+/// Refuse to allocate for scene names longer than any real one.
+const MAX_NAME_LENGTH: usize = 1 << 12;
+
+/// A rectangle of pixels handed out to a worker.
+#[derive(Clone, Debug)]
+pub(crate) struct Tile {
+    pub id: u32,
+    pub x: Range<i32>,
+    pub y: Range<i32>,
+}
+
+impl Tile {
+    pub fn len(&self) -> usize {
+        self.x.len() * self.y.len()
+    }
+}
+
+/// Everything sent between coordinator and workers. The coordinator opens
+/// with a `Job`, then alternates `Tile`s with the worker's `Pixels`, and
+/// finally sends `Done`.
+pub(crate) enum Message {
+    Job { scene: String, seed: u64 },
+    Tile(Tile),
+    Pixels { id: u32, pixels: Vec<[f32; 3]> },
+    Done,
+}
+
+impl Message {
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut buf = Vec::new();
+        match self {
+            Message::Job { scene, seed } => {
+                buf.push(JOB);
+                buf.extend((scene.len() as u32).to_le_bytes());
+                buf.extend(scene.as_bytes());
+                buf.extend(seed.to_le_bytes());
+            }
+            Message::Tile(tile) => {
+                buf.push(TILE);
+                buf.extend(tile.id.to_le_bytes());
+                for v in [tile.x.start, tile.x.end, tile.y.start, tile.y.end] {
+                    buf.extend(v.to_le_bytes());
+                }
+            }
+            Message::Pixels { id, pixels } => {
+                buf.push(PIXELS);
+                buf.extend(id.to_le_bytes());
+                buf.extend((pixels.len() as u32).to_le_bytes());
+                for v in pixels.iter().flatten() {
+                    buf.extend(v.to_le_bytes());
+                }
+            }
+            Message::Done => buf.push(DONE),
+        }
+        writer.write_all(&buf)?;
+        writer.flush()
+    }
+
+    /// Reads the next message, refusing `Pixels` with more than `max_pixels`
+    /// before allocating anything for them.
+    pub fn read(reader: &mut impl Read, max_pixels: usize) -> io::Result<Self> {
+        match read_array::<1>(reader)?[0] {
+            JOB => {
+                let length = read_length(reader, MAX_NAME_LENGTH)?;
+                let mut scene = vec![0; length];
+                reader.read_exact(&mut scene)?;
+                let scene = String::from_utf8(scene)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let seed = u64::from_le_bytes(read_array(reader)?);
+                Ok(Message::Job { scene, seed })
+            }
+            TILE => {
+                let id = u32::from_le_bytes(read_array(reader)?);
+                let mut v = [0; 4];
+                for v in v.iter_mut() {
+                    *v = i32::from_le_bytes(read_array(reader)?);
+                }
+                Ok(Message::Tile(Tile {
+                    id,
+                    x: v[0]..v[1],
+                    y: v[2]..v[3],
+                }))
+            }
+            PIXELS => {
+                let id = u32::from_le_bytes(read_array(reader)?);
+                let length = read_length(reader, max_pixels)?;
+                let mut pixels = Vec::with_capacity(length);
+                for _ in 0..length {
+                    let mut pixel = [0.0; 3];
+                    for c in pixel.iter_mut() {
+                        *c = f32::from_le_bytes(read_array(reader)?);
+                    }
+                    pixels.push(pixel);
+                }
+                Ok(Message::Pixels { id, pixels })
+            }
+            DONE => Ok(Message::Done),
+            kind => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown message kind {}", kind),
+            )),
+        }
+    }
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_length(reader: &mut impl Read, max: usize) -> io::Result<usize> {
+    let length = u32::from_le_bytes(read_array(reader)?) as usize;
+    if length > max {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message length {} is over {}", length, max),
+        ));
+    }
+    Ok(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixels(count: usize) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let pixels = vec![[0.25, 0.5, 1.0]; count];
+        Message::Pixels { id: 3, pixels }.write(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn pixels_up_to_the_tile_size_are_read() {
+        let buf = pixels(16);
+        let Message::Pixels { id, pixels } = Message::read(&mut &buf[..], 16).unwrap() else {
+            panic!("not pixels");
+        };
+        assert_eq!((id, pixels.len()), (3, 16));
+        assert_eq!(pixels[15], [0.25, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn pixels_over_the_tile_size_are_refused() {
+        let buf = pixels(17);
+        let error = Message::read(&mut &buf[..], 16).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn long_scene_names_are_refused() {
+        let mut buf = Vec::new();
+        let scene = "x".repeat(MAX_NAME_LENGTH + 1);
+        Message::Job { scene, seed: 1 }.write(&mut buf).unwrap();
+        let error = Message::read(&mut &buf[..], 0).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
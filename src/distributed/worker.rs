@@ -0,0 +1,54 @@
+use std::io::{self, BufReader, BufWriter};
+use std::net::{TcpStream, ToSocketAddrs};
+
+use crate::distributed::protocol::Message;
+use crate::distributed::SceneBuilder;
+use crate::error::{Error, Result};
+
+/// Connects to the coordinator at `address` and renders the tiles it hands
+/// out until it reports the image is finished.
+pub fn run_worker(address: impl ToSocketAddrs, scenes: SceneBuilder) -> Result<()> {
+    let stream = TcpStream::connect(address)?;
+    stream.set_nodelay(true)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = BufWriter::new(stream);
+
+    // The coordinator never sends pixels.
+    let Message::Job { scene, seed } = Message::read(&mut reader, 0)? else {
+        return Err(unexpected().into());
+    };
+    let (mut camera, world) =
+        scenes(&scene, seed).ok_or_else(|| Error::invalid(format!("unknown scene {}", scene)))?;
+    camera
+        .observer()
+        .message(&format!("Rendering {} with seed {}", scene, seed));
+
+    loop {
+        match Message::read(&mut reader, 0)? {
+            Message::Tile(tile) => {
+                let pixels = camera
+                    .render_tile(tile.x, tile.y, &world)?
+                    .iter()
+                    .map(|c| [c.r() as f32, c.g() as f32, c.b() as f32])
+                    .collect();
+                Message::Pixels {
+                    id: tile.id,
+                    pixels,
+                }
+                .write(&mut writer)?;
+                camera
+                    .observer()
+                    .message(&format!("Rendered tile {}", tile.id));
+            }
+            Message::Done => return Ok(()),
+            _ => return Err(unexpected().into()),
+        }
+    }
+}
+
+fn unexpected() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "unexpected message from coordinator",
+    )
+}
@@ -0,0 +1,66 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// Everything that can go wrong loading or rendering a scene.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Image(image::ImageError),
+    /// A scene, camera or constructor parameter out of its valid range.
+    InvalidParameter(String),
+    /// A ray hit an object which has no material to shade it with.
+    MissingMaterial,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn invalid(message: impl Into<String>) -> Self {
+        Error::InvalidParameter(message.into())
+    }
+
+    /// Fails unless `value` is finite and greater than zero.
+    pub(crate) fn check_positive(name: &str, value: f64) -> Result<()> {
+        if value.is_finite() && value > 0.0 {
+            Ok(())
+        } else {
+            Err(Error::invalid(format!(
+                "{} must be positive, got {}",
+                name, value
+            )))
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Image(e) => write!(f, "image error: {}", e),
+            Error::InvalidParameter(message) => write!(f, "invalid parameter: {}", message),
+            Error::MissingMaterial => write!(f, "hit an object without a material"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(value: image::ImageError) -> Self {
+        Error::Image(value)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::error::{Error, Result};
+use crate::filter::Filter;
+
+/// Equal weight for every sample within `radius`; a radius of half a pixel
+/// averages exactly the samples inside the pixel.
+pub struct BoxFilter {
+    radius: f64,
+}
+
+impl BoxFilter {
+    pub fn new(radius: f64) -> Result<Self> {
+        Error::check_positive("filter radius", radius)?;
+        Ok(Self { radius })
+    }
+}
+
+impl Default for BoxFilter {
+    fn default() -> Self {
+        Self { radius: 0.5 }
+    }
+}
+
+impl Filter for BoxFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64) -> f64 {
+        if x.abs() <= self.radius {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::error::{Error, Result};
+use crate::filter::Filter;
+
+/// A Gaussian with standard deviation `sigma`, shifted down to reach zero at
+/// `radius`.
+pub struct GaussianFilter {
+    radius: f64,
+    sigma: f64,
+}
+
+impl GaussianFilter {
+    pub fn new(radius: f64, sigma: f64) -> Result<Self> {
+        Error::check_positive("filter radius", radius)?;
+        Error::check_positive("gaussian sigma", sigma)?;
+        Ok(Self { radius, sigma })
+    }
+
+    fn gaussian(&self, x: f64) -> f64 {
+        (-x * x / (2.0 * self.sigma * self.sigma)).exp()
+    }
+}
+
+impl Filter for GaussianFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64) -> f64 {
+        (self.gaussian(x) - self.gaussian(self.radius)).max(0.0)
+    }
+}
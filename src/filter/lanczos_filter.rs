@@ -0,0 +1,40 @@
+use std::f64::consts::PI;
+
+use crate::error::{Error, Result};
+use crate::filter::Filter;
+
+/// A sinc windowed by a sinc `tau` times wider, cut off at `radius`.
+pub struct LanczosFilter {
+    radius: f64,
+    tau: f64,
+}
+
+impl LanczosFilter {
+    pub fn new(radius: f64, tau: f64) -> Result<Self> {
+        Error::check_positive("filter radius", radius)?;
+        Error::check_positive("lanczos tau", tau)?;
+        Ok(Self { radius, tau })
+    }
+
+    fn sinc(x: f64) -> f64 {
+        if x.abs() < 1e-5 {
+            1.0
+        } else {
+            (PI * x).sin() / (PI * x)
+        }
+    }
+}
+
+impl Filter for LanczosFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64) -> f64 {
+        if x.abs() > self.radius {
+            0.0
+        } else {
+            Self::sinc(x) * Self::sinc(x / self.tau)
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::error::{Error, Result};
+use crate::filter::Filter;
+
+/// The Mitchell-Netravali cubic, stretched to `radius`. `b = c = 1/3` is the
+/// recommended balance between ringing and blurring.
+pub struct MitchellFilter {
+    radius: f64,
+    b: f64,
+    c: f64,
+}
+
+impl MitchellFilter {
+    pub fn new(radius: f64, b: f64, c: f64) -> Result<Self> {
+        Error::check_positive("filter radius", radius)?;
+        Ok(Self { radius, b, c })
+    }
+}
+
+impl Filter for MitchellFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64) -> f64 {
+        let x = (2.0 * x / self.radius).abs();
+        let (b, c) = (self.b, self.c);
+        let value = if x > 2.0 {
+            0.0
+        } else if x > 1.0 {
+            (-b - 6.0 * c) * x * x * x
+                + (6.0 * b + 30.0 * c) * x * x
+                + (-12.0 * b - 48.0 * c) * x
+                + (8.0 * b + 24.0 * c)
+        } else {
+            (12.0 - 9.0 * b - 6.0 * c) * x * x * x
+                + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+                + (6.0 - 2.0 * b)
+        };
+        value / 6.0
+    }
+}
@@ -0,0 +1,74 @@
+mod box_filter;
+mod gaussian_filter;
+mod lanczos_filter;
+mod mitchell_filter;
+mod tent_filter;
+
+pub use box_filter::BoxFilter;
+pub use gaussian_filter::GaussianFilter;
+pub use lanczos_filter::LanczosFilter;
+pub use mitchell_filter::MitchellFilter;
+pub use tent_filter::TentFilter;
+
+/// A separable pixel reconstruction filter, given by its profile along one axis.
+pub trait Filter {
+    /// Half width of the filter in pixels.
+    fn radius(&self) -> f64;
+
+    /// The filter profile at `x` pixels from the pixel centre.
+    fn evaluate(&self, x: f64) -> f64;
+}
+
+const TABLE_SIZE: usize = 256;
+
+/// Importance samples offsets from the pixel centre proportional to the
+/// magnitude of a filter. Samples from negative lobes carry negative weight.
+pub(crate) struct FilterSampler {
+    radius: f64,
+    cdf: Vec<f64>,
+    signs: Vec<f64>,
+}
+
+impl FilterSampler {
+    pub(crate) fn new(filter: &dyn Filter) -> Self {
+        let radius = filter.radius();
+        let width = 2.0 * radius / TABLE_SIZE as f64;
+
+        let mut total = 0.0;
+        let mut cdf = Vec::with_capacity(TABLE_SIZE);
+        let mut signs = Vec::with_capacity(TABLE_SIZE);
+        for i in 0..TABLE_SIZE {
+            let value = filter.evaluate(-radius + (i as f64 + 0.5) * width);
+            total += value.abs();
+            cdf.push(total);
+            signs.push(if value < 0.0 { -1.0 } else { 1.0 });
+        }
+
+        Self { radius, cdf, signs }
+    }
+
+    fn sample_1d(&self, u: f64) -> (f64, f64) {
+        let total = self.cdf[TABLE_SIZE - 1];
+        if total <= 0.0 {
+            return (0.0, 1.0);
+        }
+
+        let target = u * total;
+        let i = self
+            .cdf
+            .partition_point(|c| *c <= target)
+            .min(TABLE_SIZE - 1);
+        let below = if i == 0 { 0.0 } else { self.cdf[i - 1] };
+        let within = ((target - below) / (self.cdf[i] - below)).clamp(0.0, 1.0);
+        let width = 2.0 * self.radius / TABLE_SIZE as f64;
+        let x = -self.radius + (i as f64 + within) * width;
+        (x, self.signs[i])
+    }
+
+    /// An offset from the pixel centre for a uniform 2D sample, and its weight.
+    pub(crate) fn sample(&self, (u1, u2): (f64, f64)) -> (f64, f64, f64) {
+        let (x, x_sign) = self.sample_1d(u1);
+        let (y, y_sign) = self.sample_1d(u2);
+        (x, y, x_sign * y_sign)
+    }
+}
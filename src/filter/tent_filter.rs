@@ -0,0 +1,24 @@
+use crate::error::{Error, Result};
+use crate::filter::Filter;
+
+/// Weight falling off linearly to zero at `radius`.
+pub struct TentFilter {
+    radius: f64,
+}
+
+impl TentFilter {
+    pub fn new(radius: f64) -> Result<Self> {
+        Error::check_positive("filter radius", radius)?;
+        Ok(Self { radius })
+    }
+}
+
+impl Filter for TentFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, x: f64) -> f64 {
+        (self.radius - x.abs()).max(0.0)
+    }
+}
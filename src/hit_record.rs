@@ -1,22 +1,49 @@
+use crate::hittable::Hittable;
 use crate::material::Material;
 use crate::point3::Point3;
 use crate::ray::Ray;
 use crate::vec3::Vec3;
-use std::rc::Rc;
 
-#[derive(Default, Clone)]
-pub struct HitRecord {
+/// Where a ray hit an object. Borrows the material and, for surface data left
+/// to be computed later, the object from the scene, so filling one in never
+/// touches a reference count.
+#[derive(Clone, Copy)]
+pub struct HitRecord<'a> {
     pub p: Point3,
     pub normal: Vec3,
-    pub material: Option<Rc<dyn Material>>,
+    /// ∂p/∂u, unnormalised.
+    pub tangent: Vec3,
+    /// ∂p/∂v, unnormalised.
+    pub bitangent: Vec3,
+    material: Option<&'a dyn Material>,
+    deferred: Option<&'a dyn Hittable>,
     pub t: f64,
     pub u: f64,
     pub v: f64,
     pub front_face: bool,
+    pub outside_ior: f64,
 }
 
-impl HitRecord {
-    pub(crate) fn set_face_normal(&mut self, r: &Ray, outward_normal: Vec3) {
+impl Default for HitRecord<'_> {
+    fn default() -> Self {
+        Self {
+            p: Default::default(),
+            normal: Default::default(),
+            tangent: Default::default(),
+            bitangent: Default::default(),
+            material: None,
+            deferred: None,
+            t: 0.0,
+            u: 0.0,
+            v: 0.0,
+            front_face: false,
+            outside_ior: 1.0,
+        }
+    }
+}
+
+impl<'a> HitRecord<'a> {
+    pub fn set_face_normal(&mut self, r: &Ray, outward_normal: Vec3) {
         self.front_face = r.direction().dot(&outward_normal) < 0.0;
         self.normal = if self.front_face {
             outward_normal
@@ -24,4 +51,38 @@ impl HitRecord {
             -outward_normal
         }
     }
+
+    pub fn outward_normal(&self) -> Vec3 {
+        if self.front_face {
+            self.normal
+        } else {
+            -self.normal
+        }
+    }
+
+    pub fn material(&self) -> Option<&'a dyn Material> {
+        self.material
+    }
+
+    /// Records a hit on a surface of `material` whose data is complete.
+    pub fn set_material(&mut self, material: &'a dyn Material) {
+        self.material = Some(material);
+        self.deferred = None;
+    }
+
+    /// Records a hit on `object`, leaving the texture coordinates and tangents
+    /// for [`Hittable::surface`] to fill in once the hit is known to be the
+    /// closest one.
+    pub fn defer_surface(&mut self, object: &'a dyn Hittable, material: &'a dyn Material) {
+        self.material = Some(material);
+        self.deferred = Some(object);
+    }
+
+    /// Completes any surface data deferred by the object `r` hit. Must be
+    /// called before the record is shaded or moved out of the object's space.
+    pub fn finish(&mut self, r: &Ray) {
+        if let Some(object) = self.deferred.take() {
+            object.surface(r, self);
+        }
+    }
 }
@@ -0,0 +1,118 @@
+use crate::aabb::Aabb;
+use crate::error::{Error, Result};
+use crate::hit_record::HitRecord;
+use crate::hittable::{Hittable, Span};
+use crate::interval::Interval;
+use crate::keyframes::Keyframes;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::transform::Transform;
+use std::rc::Rc;
+
+/// Extra samples between keys when bounding rotating objects.
+const BOUND_STEPS: usize = 16;
+
+/// Moves an object along keyframed transforms, evaluated at each ray's time.
+pub struct Animated {
+    object: Rc<dyn Hittable>,
+    keyframes: Keyframes<Transform>,
+    bbox: Aabb,
+}
+
+impl Animated {
+    /// Fails if `keyframes` is empty.
+    pub fn new(object: Rc<dyn Hittable>, keyframes: Keyframes<Transform>) -> Result<Self> {
+        if keyframes.is_empty() {
+            return Err(Error::invalid("an animation needs at least one key"));
+        }
+        let bbox = Self::bound(object.bounding_box(), &keyframes);
+        Ok(Self {
+            object,
+            keyframes,
+            bbox,
+        })
+    }
+
+    fn bound(bbox: Aabb, keyframes: &Keyframes<Transform>) -> Aabb {
+        let corners: Vec<Point3> = (0..8)
+            .map(|i| {
+                Point3::new(
+                    if i & 1 == 0 { bbox.x.min } else { bbox.x.max },
+                    if i & 2 == 0 { bbox.y.min } else { bbox.y.max },
+                    if i & 4 == 0 { bbox.z.min } else { bbox.z.max },
+                )
+            })
+            .collect();
+
+        let times: Vec<f64> = keyframes.times().collect();
+        let mut samples = vec![times[0]];
+        for pair in times.windows(2) {
+            samples.extend(
+                (1..=BOUND_STEPS)
+                    .map(|s| pair[0] + (pair[1] - pair[0]) * s as f64 / BOUND_STEPS as f64),
+            );
+        }
+
+        samples
+            .into_iter()
+            .flat_map(|time| {
+                let transform = keyframes.at(time);
+                corners
+                    .iter()
+                    .map(move |c| transform.apply_point(c))
+                    .collect::<Vec<_>>()
+            })
+            .map(|p| Aabb::from_points(p, p))
+            .reduce(Aabb::from_boxes)
+            .unwrap()
+            .pad()
+    }
+
+    fn to_object(transform: &Transform, r: &Ray) -> Ray {
+        Ray::new(
+            transform.inverse_point(&r.origin()),
+            transform.inverse_vector(&r.direction()),
+            r.time(),
+        )
+        .with_wavelength(r.wavelength())
+    }
+
+    fn to_world(transform: &Transform, rec: &mut HitRecord) {
+        rec.p = transform.apply_point(&rec.p);
+        rec.normal = transform.apply_normal(&rec.normal);
+        rec.tangent = transform.apply_vector(&rec.tangent);
+        rec.bitangent = transform.apply_vector(&rec.bitangent);
+    }
+}
+
+impl Hittable for Animated {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let transform = self.keyframes.at(r.time());
+        let r = Self::to_object(&transform, r);
+        if !self.object.hit(&r, ray_t, rec) {
+            return false;
+        }
+
+        rec.finish(&r);
+        Self::to_world(&transform, rec);
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn is_closed(&self) -> bool {
+        self.object.is_closed()
+    }
+
+    fn spans(&self, r: &Ray) -> Option<Vec<Span<'_>>> {
+        let transform = self.keyframes.at(r.time());
+        let mut spans = self.object.spans(&Self::to_object(&transform, r))?;
+        for span in &mut spans {
+            Self::to_world(&transform, &mut span.enter);
+            Self::to_world(&transform, &mut span.exit);
+        }
+        Some(spans)
+    }
+}
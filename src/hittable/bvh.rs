@@ -80,7 +80,7 @@ impl BvhNode {
 }
 
 impl Hittable for BvhNode {
-    fn hit(&self, r: &Ray, mut ray_t: Interval, rec: &mut HitRecord) -> bool {
+    fn hit<'a>(&'a self, r: &Ray, mut ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
         if !self.bbox.hit(r, &mut ray_t) {
             return false;
         }
@@ -95,6 +95,11 @@ impl Hittable for BvhNode {
         hit_left || hit_right
     }
 
+    fn occluded(&self, r: &Ray, mut ray_t: Interval) -> bool {
+        self.bbox.hit(r, &mut ray_t)
+            && (self.left.occluded(r, ray_t) || self.right.occluded(r, ray_t))
+    }
+
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
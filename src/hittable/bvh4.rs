@@ -0,0 +1,348 @@
+use crate::aabb::Aabb;
+use crate::hit_record::HitRecord;
+use crate::hittable::{Hittable, HittableList, PACKET_SIZE};
+use crate::interval::Interval;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::simd::F3;
+use std::rc::Rc;
+
+/// Deep enough for any tree built from median splits, which stays balanced.
+const STACK_SIZE: usize = 64;
+
+/// A bounding volume hierarchy with four children per node, whose boxes are
+/// tested against a ray all at once. Traces coherent rays, such as camera rays,
+/// faster than [`BvhNode`](super::BvhNode), and can trace them in packets.
+pub struct Bvh4 {
+    nodes: Vec<Node>,
+    objects: Vec<Rc<dyn Hittable>>,
+    bbox: Aabb,
+}
+
+#[derive(Clone, Copy)]
+enum Child {
+    Empty,
+    Node(u32),
+    Object(u32),
+}
+
+/// The boxes of four children, one per lane. Missing children get a box at
+/// infinity, which no ray can hit.
+struct Node {
+    min: [F3; 3],
+    max: [F3; 3],
+    children: [Child; 4],
+}
+
+/// A ray split into lanes for testing against the boxes of a node.
+#[derive(Clone, Copy, Default)]
+struct SlabRay {
+    origin: [F3; 3],
+    inv_direction: [F3; 3],
+}
+
+struct Item {
+    index: u32,
+    bbox: Aabb,
+    centroid: Point3,
+}
+
+impl Bvh4 {
+    pub fn from_hittable_list(list: HittableList) -> Self {
+        let objects = list.objects;
+        let mut items: Vec<Item> = objects
+            .iter()
+            .enumerate()
+            .map(|(index, object)| {
+                let bbox = object.bounding_box();
+                Item {
+                    index: index as u32,
+                    bbox,
+                    centroid: Point3::new(
+                        (bbox.x.min + bbox.x.max) / 2.0,
+                        (bbox.y.min + bbox.y.max) / 2.0,
+                        (bbox.z.min + bbox.z.max) / 2.0,
+                    ),
+                }
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        let mut bbox = Aabb::default();
+        if !items.is_empty() {
+            bbox = Self::build(&mut nodes, &mut items).0;
+        }
+        Self {
+            nodes,
+            objects,
+            bbox,
+        }
+    }
+
+    /// Adds the subtree over `items` to `nodes`, returning its box and root.
+    fn build(nodes: &mut Vec<Node>, items: &mut [Item]) -> (Aabb, u32) {
+        let index = nodes.len();
+        nodes.push(Node {
+            min: [F3::scalar(f64::INFINITY); 3],
+            max: [F3::scalar(f64::INFINITY); 3],
+            children: [Child::Empty; 4],
+        });
+
+        let mut groups: Vec<&mut [Item]> = Vec::with_capacity(4);
+        if items.len() <= 4 {
+            groups.extend(items.chunks_mut(1));
+        } else {
+            let (left, right) = Self::split(items);
+            for half in [left, right] {
+                let (a, b) = Self::split(half);
+                groups.push(a);
+                groups.push(b);
+            }
+        }
+
+        let mut min = [[f64::INFINITY; 4]; 3];
+        let mut max = [[f64::INFINITY; 4]; 3];
+        let mut children = [Child::Empty; 4];
+        let mut bbox: Option<Aabb> = None;
+        for (lane, group) in groups.into_iter().enumerate() {
+            let (child_box, child) = if let [item] = group {
+                (item.bbox, Child::Object(item.index))
+            } else {
+                let (child_box, node) = Self::build(nodes, group);
+                (child_box, Child::Node(node))
+            };
+            for axis in 0..3 {
+                min[axis][lane] = child_box.axis(axis as i32).min;
+                max[axis][lane] = child_box.axis(axis as i32).max;
+            }
+            children[lane] = child;
+            bbox = Some(bbox.map_or(child_box, |b| Aabb::from_boxes(b, child_box)));
+        }
+
+        nodes[index] = Node {
+            min: min.map(F3::from_wide),
+            max: max.map(F3::from_wide),
+            children,
+        };
+        (bbox.unwrap_or_default(), index as u32)
+    }
+
+    /// Halves `items` at the median centroid along the axis where the
+    /// centroids spread the most.
+    fn split(items: &mut [Item]) -> (&mut [Item], &mut [Item]) {
+        let spread = |axis: i32| {
+            let (lo, hi) = items
+                .iter()
+                .map(|item| item.centroid.axis(axis))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| {
+                    (lo.min(c), hi.max(c))
+                });
+            hi - lo
+        };
+        let axis = (0..3)
+            .max_by(|&a, &b| spread(a).total_cmp(&spread(b)))
+            .unwrap_or(0);
+
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |a, b| {
+            a.centroid.axis(axis).total_cmp(&b.centroid.axis(axis))
+        });
+        items.split_at_mut(mid)
+    }
+}
+
+impl SlabRay {
+    fn new(r: &Ray) -> Self {
+        let (origin, inv_direction) = (r.origin(), r.inv_direction());
+        Self {
+            origin: [0, 1, 2].map(|axis| F3::scalar(origin.axis(axis))),
+            inv_direction: [0, 1, 2].map(|axis| F3::scalar(inv_direction.axis(axis))),
+        }
+    }
+}
+
+impl Node {
+    /// Which of the four child boxes `r` passes through within `ray_t`, one
+    /// bit per lane, and where it enters each of them.
+    #[inline]
+    fn hit(&self, r: &SlabRay, ray_t: Interval) -> (u32, F3) {
+        let mut near = F3::scalar(ray_t.min);
+        let mut far = F3::scalar(ray_t.max);
+        for axis in 0..3 {
+            let t0 = (self.min[axis] - r.origin[axis]) * r.inv_direction[axis];
+            let t1 = (self.max[axis] - r.origin[axis]) * r.inv_direction[axis];
+            // As in `Aabb::hit`, a ray lying in a slab's plane leaves the
+            // bounds as they were.
+            near = t0.slab_near(t1, near);
+            far = t0.slab_far(t1, far);
+        }
+        (near.lt_mask(far), near)
+    }
+}
+
+/// The set bits of `mask`, lowest first.
+fn bits(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (mask != 0).then(|| {
+            let bit = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            bit
+        })
+    })
+}
+
+impl Hittable for Bvh4 {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+
+        let slab = SlabRay::new(r);
+        let mut closest = ray_t.max;
+        let mut hit_anything = false;
+        let mut stack = [(Child::Empty, 0.0); STACK_SIZE];
+        stack[0] = (Child::Node(0), ray_t.min);
+        let mut len = 1;
+
+        while len > 0 {
+            len -= 1;
+            let (child, near) = stack[len];
+            if near > closest {
+                continue;
+            }
+            match child {
+                Child::Empty => {}
+                Child::Object(index) => {
+                    let t = Interval::new(ray_t.min, closest);
+                    if self.objects[index as usize].hit(r, t, rec) {
+                        hit_anything = true;
+                        closest = rec.t;
+                    }
+                }
+                Child::Node(index) => {
+                    let node = &self.nodes[index as usize];
+                    let (mask, near) = node.hit(&slab, Interval::new(ray_t.min, closest));
+                    let near = near.wide();
+
+                    // Pushed farthest first, so the nearest child is visited
+                    // first and shortens the ray for the others.
+                    let mut hits = [(Child::Empty, 0.0); 4];
+                    let mut count = 0;
+                    for lane in bits(mask as u64) {
+                        hits[count] = (node.children[lane], near[lane]);
+                        count += 1;
+                    }
+                    let hits = &mut hits[..count];
+                    hits.sort_unstable_by(|a, b| b.1.total_cmp(&a.1));
+                    stack[len..len + count].copy_from_slice(hits);
+                    len += count;
+                }
+            }
+        }
+
+        hit_anything
+    }
+
+    fn occluded(&self, r: &Ray, ray_t: Interval) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+
+        let slab = SlabRay::new(r);
+        let mut stack = [Child::Empty; STACK_SIZE];
+        stack[0] = Child::Node(0);
+        let mut len = 1;
+
+        while len > 0 {
+            len -= 1;
+            match stack[len] {
+                Child::Empty => {}
+                Child::Object(index) => {
+                    if self.objects[index as usize].occluded(r, ray_t) {
+                        return true;
+                    }
+                }
+                Child::Node(index) => {
+                    let node = &self.nodes[index as usize];
+                    let (mask, _) = node.hit(&slab, ray_t);
+                    for lane in bits(mask as u64) {
+                        stack[len] = node.children[lane];
+                        len += 1;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Walks the tree once for the whole packet, visiting each node for every
+    /// ray still able to hit something inside it.
+    fn hit_packet<'a>(&'a self, rays: &[Ray], ray_t: Interval, recs: &mut [HitRecord<'a>]) -> u64 {
+        assert!(rays.len() <= PACKET_SIZE && recs.len() >= rays.len());
+        if self.nodes.is_empty() || rays.is_empty() {
+            return 0;
+        }
+
+        let mut slabs = [SlabRay::default(); PACKET_SIZE];
+        for (slab, r) in slabs.iter_mut().zip(rays) {
+            *slab = SlabRay::new(r);
+        }
+        let mut closest = [ray_t.max; PACKET_SIZE];
+        let mut hits = 0;
+        let mut stack = [(Child::Empty, 0); STACK_SIZE];
+        stack[0] = (Child::Node(0), u64::MAX >> (PACKET_SIZE - rays.len()));
+        let mut len = 1;
+
+        while len > 0 {
+            len -= 1;
+            let (child, active) = stack[len];
+            match child {
+                Child::Empty => {}
+                Child::Object(index) => {
+                    let object = &self.objects[index as usize];
+                    for k in bits(active) {
+                        let t = Interval::new(ray_t.min, closest[k]);
+                        if object.hit(&rays[k], t, &mut recs[k]) {
+                            hits |= 1 << k;
+                            closest[k] = recs[k].t;
+                        }
+                    }
+                }
+                Child::Node(index) => {
+                    let node = &self.nodes[index as usize];
+                    // Which rays go on into each child, and, to order the
+                    // children, where the first of them enters it.
+                    let mut lanes = [0u64; 4];
+                    let mut near = [f64::INFINITY; 4];
+                    for k in bits(active) {
+                        let t = Interval::new(ray_t.min, closest[k]);
+                        let (mask, entry) = node.hit(&slabs[k], t);
+                        let entry = entry.wide();
+                        for lane in bits(mask as u64) {
+                            if lanes[lane] == 0 {
+                                near[lane] = entry[lane];
+                            }
+                            lanes[lane] |= 1 << k;
+                        }
+                    }
+
+                    let mut order = [0, 1, 2, 3];
+                    order.sort_unstable_by(|&a, &b| near[b].total_cmp(&near[a]));
+                    for lane in order {
+                        if lanes[lane] != 0 {
+                            stack[len] = (node.children[lane], lanes[lane]);
+                            len += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        hits
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -0,0 +1,259 @@
+use crate::aabb::Aabb;
+use crate::error::{Error, Result};
+use crate::hit_record::HitRecord;
+use crate::hittable::{alpha_test, Hittable, Span};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::point3::Point3;
+use crate::poly::Roots;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec3::Vec3;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+#[derive(Copy, Clone, Default)]
+enum Part {
+    #[default]
+    Side,
+    Base,
+}
+
+/// A cone with a base of `radius` around `base` and its apex at `base + axis`.
+pub struct Cone {
+    base: Point3,
+    uvw: Onb,
+    height: f64,
+    radius: f64,
+    capped: bool,
+    mat: Rc<dyn Material>,
+    bbox: Aabb,
+    alpha: Option<Rc<dyn Texture>>,
+}
+
+impl Cone {
+    pub fn new(
+        base: Point3,
+        axis: Vec3,
+        radius: f64,
+        capped: bool,
+        mat: Rc<dyn Material>,
+    ) -> Result<Self> {
+        Error::check_positive("cone radius", radius)?;
+        Error::check_positive("cone height", axis.length())?;
+        let uvw = Onb::build_from_w(&axis);
+        let apex = base + axis;
+        Ok(Self {
+            base,
+            uvw,
+            height: axis.length(),
+            radius,
+            capped,
+            mat,
+            bbox: Aabb::from_boxes(
+                Aabb::from_disk(base, uvw.w(), radius),
+                Aabb::from_points(apex, apex),
+            )
+            .pad(),
+            alpha: None,
+        })
+    }
+
+    /// Opacity mask; hits where the mask is transparent are ignored.
+    pub fn alpha(mut self, alpha: Rc<dyn Texture>) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// All intersections of the ray with the surface, nearest first, with the
+    /// ray's origin and direction in the cone's local frame.
+    fn intersections(&self, r: &Ray) -> (Vec3, Vec3, Roots<(f64, Part)>) {
+        let o = self.uvw.world_to_local(&(r.origin() - self.base));
+        let d = self.uvw.world_to_local(&r.direction());
+        let mut hits = Roots::default();
+
+        // x² + y² = k²(h - z)²
+        let k = self.radius / self.height;
+        let k2 = k * k;
+        let oh = self.height - o.z();
+        let a = d.x() * d.x() + d.y() * d.y() - k2 * d.z() * d.z();
+        let half_b = o.x() * d.x() + o.y() * d.y() + k2 * oh * d.z();
+        let c = o.x() * o.x() + o.y() * o.y() - k2 * oh * oh;
+
+        let mut roots = Roots::default();
+        if a.abs() < 1e-12 {
+            if half_b.abs() >= 1e-12 {
+                roots.push(-c / (2.0 * half_b));
+            }
+        } else {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0.0 {
+                let sqrt_d = discriminant.sqrt();
+                roots.push((-half_b - sqrt_d) / a);
+                roots.push((-half_b + sqrt_d) / a);
+            }
+        }
+        for &t in roots.iter() {
+            let z = o.z() + t * d.z();
+            if (0.0..=self.height).contains(&z) {
+                hits.push((t, Part::Side));
+            }
+        }
+
+        if self.capped && d.z().abs() > 1e-12 {
+            let t = -o.z() / d.z();
+            let x = o.x() + t * d.x();
+            let y = o.y() + t * d.y();
+            if x * x + y * y <= self.radius * self.radius {
+                hits.push((t, Part::Base));
+            }
+        }
+
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+        (o, d, hits)
+    }
+
+    /// Local outward normal at a local point.
+    fn local_normal(&self, p: &Vec3, part: Part) -> Vec3 {
+        match part {
+            Part::Side => {
+                let k = self.radius / self.height;
+                Vec3::new(p.x(), p.y(), k * k * (self.height - p.z())).unit_vector()
+            }
+            Part::Base => Vec3::new(0.0, 0.0, -1.0),
+        }
+    }
+
+    /// Texture coordinates and tangents at a local point.
+    fn local_surface(&self, p: &Vec3, part: Part) -> ((f64, f64), Vec3, Vec3) {
+        match part {
+            Part::Side => {
+                let rho = (p.x() * p.x() + p.y() * p.y()).sqrt();
+                let phi = p.y().atan2(p.x());
+                let slope = if rho > 0.0 {
+                    Vec3::new(
+                        -self.radius * p.x() / rho,
+                        -self.radius * p.y() / rho,
+                        self.height,
+                    )
+                } else {
+                    Vec3::new(0.0, 0.0, self.height)
+                };
+                (
+                    ((phi + PI) / (2.0 * PI), p.z() / self.height),
+                    2.0 * PI * Vec3::new(-p.y(), p.x(), 0.0),
+                    slope,
+                )
+            }
+            Part::Base => (
+                (
+                    0.5 * (p.x() / self.radius + 1.0),
+                    0.5 * (p.y() / self.radius + 1.0),
+                ),
+                Vec3::new(2.0 * self.radius, 0.0, 0.0),
+                Vec3::new(0.0, 2.0 * self.radius, 0.0),
+            ),
+        }
+    }
+
+    /// Fills in the hit point and normal, leaving the rest to `set_surface`.
+    fn set_hit_point(
+        &self,
+        r: &Ray,
+        (o, d): (Vec3, Vec3),
+        t: f64,
+        part: Part,
+        rec: &mut HitRecord,
+    ) {
+        rec.t = t;
+        rec.p = r.at(t);
+        let normal = self.local_normal(&(o + t * d), part);
+        rec.set_face_normal(r, self.uvw.local_vec(&normal));
+    }
+
+    fn set_surface(&self, p: &Vec3, part: Part, rec: &mut HitRecord) {
+        let ((u, v), tangent, bitangent) = self.local_surface(p, part);
+        rec.u = u;
+        rec.v = v;
+        rec.tangent = self.uvw.local_vec(&tangent);
+        rec.bitangent = self.uvw.local_vec(&bitangent);
+    }
+
+    fn set_hit_record<'a>(
+        &'a self,
+        r: &Ray,
+        (o, d): (Vec3, Vec3),
+        t: f64,
+        part: Part,
+        rec: &mut HitRecord<'a>,
+    ) {
+        self.set_hit_point(r, (o, d), t, part, rec);
+        self.set_surface(&(o + t * d), part, rec);
+        rec.set_material(self.mat.as_ref());
+    }
+}
+
+impl Hittable for Cone {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let (o, d, hits) = self.intersections(r);
+
+        for &(t, part) in hits.iter() {
+            if !ray_t.surrounds(t) {
+                continue;
+            }
+
+            if self.alpha.is_some() {
+                let ((u, v), _, _) = self.local_surface(&(o + t * d), part);
+                if !alpha_test(&self.alpha, u, v, &r.at(t), r) {
+                    continue;
+                }
+            }
+
+            // The texture coordinates and tangents are only needed if no
+            // closer hit turns up.
+            self.set_hit_point(r, (o, d), t, part, rec);
+            rec.defer_surface(self, self.mat.as_ref());
+            return true;
+        }
+
+        false
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    /// Tells the base from the side by the normal, which points straight
+    /// away from the apex on the base and never away from it on the side.
+    fn surface(&self, _r: &Ray, rec: &mut HitRecord) {
+        let normal = self.uvw.world_to_local(&rec.outward_normal());
+        let part = if normal.z() < -0.5 {
+            Part::Base
+        } else {
+            Part::Side
+        };
+        self.set_surface(&self.uvw.world_to_local(&(rec.p - self.base)), part, rec);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.capped
+    }
+
+    fn spans(&self, r: &Ray) -> Option<Vec<Span<'_>>> {
+        if !self.capped {
+            return None;
+        }
+
+        let (o, d, hits) = self.intersections(r);
+        let crossings = hits
+            .iter()
+            .map(|&(t, part)| {
+                let mut rec = HitRecord::default();
+                self.set_hit_record(r, (o, d), t, part, &mut rec);
+                rec
+            })
+            .collect();
+        Some(Span::from_crossings(crossings))
+    }
+}
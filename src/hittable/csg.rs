@@ -0,0 +1,149 @@
+use crate::aabb::Aabb;
+use crate::error::{Error, Result};
+use crate::hit_record::HitRecord;
+use crate::hittable::{Hittable, Span};
+use crate::interval::Interval;
+use crate::ray::Ray;
+use std::rc::Rc;
+
+#[derive(Copy, Clone, Debug)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    /// The left object with the right one cut out of it.
+    Difference,
+}
+
+impl CsgOperation {
+    fn inside(&self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => in_left || in_right,
+            CsgOperation::Intersection => in_left && in_right,
+            CsgOperation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+/// Constructive solid geometry on two closed objects, i.e. ones answering
+/// [`Hittable::spans`]. Surfaces keep the material of the object they came from.
+pub struct Csg {
+    operation: CsgOperation,
+    left: Rc<dyn Hittable>,
+    right: Rc<dyn Hittable>,
+    bbox: Aabb,
+}
+
+impl Csg {
+    /// Fails unless both objects are closed, since the solid could not be
+    /// found for any ray otherwise.
+    pub fn new(
+        operation: CsgOperation,
+        left: Rc<dyn Hittable>,
+        right: Rc<dyn Hittable>,
+    ) -> Result<Self> {
+        if !left.is_closed() || !right.is_closed() {
+            return Err(Error::invalid(format!(
+                "{:?} of an object which is not closed",
+                operation
+            )));
+        }
+        let bbox = match operation {
+            CsgOperation::Union => Aabb::from_boxes(left.bounding_box(), right.bounding_box()),
+            CsgOperation::Intersection => left.bounding_box().intersection(&right.bounding_box()),
+            CsgOperation::Difference => left.bounding_box(),
+        };
+        Ok(Self {
+            operation,
+            left,
+            right,
+            bbox,
+        })
+    }
+
+    pub fn union(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Result<Self> {
+        Self::new(CsgOperation::Union, left, right)
+    }
+
+    pub fn intersection(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Result<Self> {
+        Self::new(CsgOperation::Intersection, left, right)
+    }
+
+    pub fn difference(left: Rc<dyn Hittable>, right: Rc<dyn Hittable>) -> Result<Self> {
+        Self::new(CsgOperation::Difference, left, right)
+    }
+}
+
+impl Hittable for Csg {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let mut bbox_t = ray_t;
+        if !self.bbox.pad().hit(r, &mut bbox_t) {
+            return false;
+        }
+        let Some(spans) = self.spans(r) else {
+            return false;
+        };
+
+        for boundary in spans.into_iter().flat_map(|span| [span.enter, span.exit]) {
+            if ray_t.surrounds(boundary.t) {
+                *rec = boundary;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn spans(&self, r: &Ray) -> Option<Vec<Span<'_>>> {
+        let left = self.left.spans(r)?;
+        let right = self.right.spans(r)?;
+
+        // Sweep over every crossing of either object in order, tracking which
+        // objects we are inside of.
+        let mut events: Vec<(HitRecord, bool, bool)> = Vec::new();
+        for (spans, is_left) in [(left, true), (right, false)] {
+            for span in spans {
+                events.push((span.enter, is_left, true));
+                events.push((span.exit, is_left, false));
+            }
+        }
+        events.sort_by(|a, b| a.0.t.total_cmp(&b.0.t));
+
+        let (mut in_left, mut in_right) = (false, false);
+        let mut result = Vec::new();
+        let mut enter: Option<HitRecord> = None;
+
+        for (mut rec, is_left, entering) in events {
+            let was_inside = self.operation.inside(in_left, in_right);
+            if is_left {
+                in_left = entering;
+            } else {
+                in_right = entering;
+            }
+            let inside = self.operation.inside(in_left, in_right);
+            if inside == was_inside {
+                continue;
+            }
+
+            // Surfaces of the subtracted object face into it.
+            if !is_left && matches!(self.operation, CsgOperation::Difference) {
+                rec.set_face_normal(r, -rec.outward_normal());
+            }
+
+            if inside {
+                enter = Some(rec);
+            } else if let Some(enter) = enter.take() {
+                result.push(Span { enter, exit: rec });
+            }
+        }
+
+        Some(result)
+    }
+}
@@ -0,0 +1,131 @@
+use crate::aabb::Aabb;
+use crate::hit_record::HitRecord;
+use crate::hittable::{alpha_test, Hittable, Span};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec3::Vec3;
+use std::rc::Rc;
+
+/// An axis aligned box with opposite corners `a` and `b`.
+pub struct Cuboid {
+    bbox: Aabb,
+    mat: Rc<dyn Material>,
+    alpha: Option<Rc<dyn Texture>>,
+}
+
+impl Cuboid {
+    pub fn new(a: Point3, b: Point3, mat: Rc<dyn Material>) -> Self {
+        Self {
+            bbox: Aabb::from_points(a, b),
+            mat,
+            alpha: None,
+        }
+    }
+
+    /// Opacity mask; hits where the mask is transparent are ignored.
+    pub fn alpha(mut self, alpha: Rc<dyn Texture>) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// Where the ray enters and exits the slabs of the box, along with the
+    /// axis of the face crossed at each.
+    fn slabs(&self, r: &Ray) -> Option<((f64, i32), (f64, i32))> {
+        let mut enter = (-f64::INFINITY, 0);
+        let mut exit = (f64::INFINITY, 0);
+
+        for axis in 0..3 {
+            let inv_d = r.inv_direction().axis(axis);
+            let orig = r.origin().axis(axis);
+            let t0 = (self.bbox.axis(axis).min - orig) * inv_d;
+            let t1 = (self.bbox.axis(axis).max - orig) * inv_d;
+            let (near, far) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+
+            if near > enter.0 {
+                enter = (near, axis);
+            }
+            if far < exit.0 {
+                exit = (far, axis);
+            }
+        }
+
+        (enter.0 <= exit.0).then_some((enter, exit))
+    }
+
+    fn set_hit_record<'a>(&'a self, r: &Ray, t: f64, axis: i32, rec: &mut HitRecord<'a>) {
+        rec.t = t;
+        rec.p = r.at(t);
+
+        let interval = self.bbox.axis(axis);
+        let sign = if rec.p.axis(axis) - interval.min < interval.max - rec.p.axis(axis) {
+            -1.0
+        } else {
+            1.0
+        };
+        let unit = |i: i32| {
+            Vec3::new(
+                (i == 0) as i32 as f64,
+                (i == 1) as i32 as f64,
+                (i == 2) as i32 as f64,
+            )
+        };
+        let (s, t) = ((axis + 1) % 3, (axis + 2) % 3);
+        let (s_range, t_range) = (self.bbox.axis(s), self.bbox.axis(t));
+
+        rec.u = (rec.p.axis(s) - s_range.min) / s_range.size();
+        rec.v = (rec.p.axis(t) - t_range.min) / t_range.size();
+        rec.set_face_normal(r, sign * unit(axis));
+        rec.tangent = s_range.size() * unit(s);
+        rec.bitangent = t_range.size() * unit(t);
+        rec.set_material(self.mat.as_ref());
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some((enter, exit)) = self.slabs(r) else {
+            return false;
+        };
+
+        for (t, axis) in [enter, exit] {
+            if !ray_t.surrounds(t) {
+                continue;
+            }
+
+            if self.alpha.is_some() {
+                let mut candidate = HitRecord::default();
+                self.set_hit_record(r, t, axis, &mut candidate);
+                if !alpha_test(&self.alpha, candidate.u, candidate.v, &candidate.p, r) {
+                    continue;
+                }
+            }
+
+            self.set_hit_record(r, t, axis, rec);
+            return true;
+        }
+
+        false
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn spans(&self, r: &Ray) -> Option<Vec<Span<'_>>> {
+        let Some(((t0, axis0), (t1, axis1))) = self.slabs(r) else {
+            return Some(vec![]);
+        };
+
+        let mut span = Span::default();
+        self.set_hit_record(r, t0, axis0, &mut span.enter);
+        self.set_hit_record(r, t1, axis1, &mut span.exit);
+        Some(vec![span])
+    }
+}
@@ -0,0 +1,43 @@
+use crate::aabb::Aabb;
+use crate::error::{Error, Result};
+use crate::hit_record::HitRecord;
+use crate::hittable::{Ellipse, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec3::Vec3;
+use std::rc::Rc;
+
+pub struct Disk {
+    ellipse: Ellipse,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, radius: f64, mat: Rc<dyn Material>) -> Result<Self> {
+        Error::check_positive("disk radius", radius)?;
+        Error::check_positive("disk normal length", normal.length())?;
+        let uvw = Onb::build_from_w(&normal);
+        Ok(Self {
+            ellipse: Ellipse::new(center, radius * uvw.u(), radius * uvw.v(), mat),
+        })
+    }
+
+    /// Opacity mask; hits where the mask is transparent are ignored.
+    pub fn alpha(mut self, alpha: Rc<dyn Texture>) -> Self {
+        self.ellipse = self.ellipse.alpha(alpha);
+        self
+    }
+}
+
+impl Hittable for Disk {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        self.ellipse.hit(r, ray_t, rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.ellipse.bounding_box()
+    }
+}
@@ -0,0 +1,68 @@
+use crate::aabb::Aabb;
+use crate::hit_record::HitRecord;
+use crate::hittable::plane::Plane;
+use crate::hittable::{alpha_test, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec3::Vec3;
+use std::rc::Rc;
+
+/// The ellipse around `center` with semi-axes `u` and `v`.
+pub struct Ellipse {
+    plane: Plane,
+    mat: Rc<dyn Material>,
+    bbox: Aabb,
+    alpha: Option<Rc<dyn Texture>>,
+}
+
+impl Ellipse {
+    pub fn new(center: Point3, u: Vec3, v: Vec3, mat: Rc<dyn Material>) -> Self {
+        let extent = Vec3::new(
+            (u.x() * u.x() + v.x() * v.x()).sqrt(),
+            (u.y() * u.y() + v.y() * v.y()).sqrt(),
+            (u.z() * u.z() + v.z() * v.z()).sqrt(),
+        );
+        Self {
+            plane: Plane::new(center, u, v),
+            mat,
+            bbox: Aabb::from_points(center - extent, center + extent).pad(),
+            alpha: None,
+        }
+    }
+
+    /// Opacity mask; hits where the mask is transparent are ignored.
+    pub fn alpha(mut self, alpha: Rc<dyn Texture>) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+}
+
+impl Hittable for Ellipse {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some((t, alpha, beta)) = self.plane.intersect(r, ray_t) else {
+            return false;
+        };
+
+        if alpha * alpha + beta * beta > 1.0 {
+            return false;
+        }
+
+        let uv = (0.5 * (alpha + 1.0), 0.5 * (beta + 1.0));
+        if !alpha_test(&self.alpha, uv.0, uv.1, &r.at(t), r) {
+            return false;
+        }
+
+        self.plane.set_hit_record(r, t, uv, self.mat.as_ref(), rec);
+        rec.tangent = 2.0 * self.plane.u();
+        rec.bitangent = 2.0 * self.plane.v();
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
@@ -19,22 +19,24 @@ impl HittableList {
 }
 
 impl Hittable for HittableList {
-    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
-        let mut tmp_rec = HitRecord::default();
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
         let mut hit_anything = false;
         let mut closest_so_far = ray_t.max;
 
         for object in self.objects.iter() {
-            if object.hit(r, Interval::new(ray_t.min, closest_so_far), &mut tmp_rec) {
+            if object.hit(r, Interval::new(ray_t.min, closest_so_far), rec) {
                 hit_anything = true;
-                closest_so_far = tmp_rec.t;
-                *rec = tmp_rec.clone();
+                closest_so_far = rec.t;
             }
         }
 
         hit_anything
     }
 
+    fn occluded(&self, r: &Ray, ray_t: Interval) -> bool {
+        self.objects.iter().any(|object| object.occluded(r, ray_t))
+    }
+
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
@@ -1,20 +1,132 @@
+mod animated;
 mod bvh;
+mod bvh4;
+mod cone;
+mod csg;
+mod cuboid;
+mod cylinder;
+mod disk;
+mod ellipse;
 mod hittable_list;
+mod plane;
 mod quad;
+mod sdf_hittable;
 mod sphere;
+mod torus;
+mod triangle;
 
 use crate::hit_record::HitRecord;
 use crate::interval::Interval;
 
 use crate::aabb::Aabb;
-use crate::Ray;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use std::rc::Rc;
+
+pub use animated::Animated;
 pub use bvh::BvhNode;
+pub use bvh4::Bvh4;
+pub use cone::Cone;
+pub use csg::{Csg, CsgOperation};
+pub use cuboid::Cuboid;
+pub use cylinder::Cylinder;
+pub use disk::Disk;
+pub use ellipse::Ellipse;
 pub use hittable_list::HittableList;
 pub use quad::Quad;
+pub use sdf_hittable::SdfHittable;
 pub use sphere::Sphere;
+pub use torus::Torus;
+pub use triangle::Triangle;
+
+/// The most rays [`Hittable::hit_packet`] takes at once, one per bit of its
+/// result.
+pub const PACKET_SIZE: usize = 64;
 
 pub trait Hittable {
-    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool;
+    /// Finds the closest hit within `ray_t`, leaving `rec` untouched on a miss
+    /// so that callers can pass the same record to several objects.
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool;
 
     fn bounding_box(&self) -> Aabb;
+
+    /// Whether anything blocks `r` within `ray_t`, for visibility tests such as
+    /// shadow rays, which need neither the closest hit nor its surface data.
+    fn occluded(&self, r: &Ray, ray_t: Interval) -> bool {
+        self.hit(r, ray_t, &mut HitRecord::default())
+    }
+
+    /// Finds the closest hit within `ray_t` of each of up to [`PACKET_SIZE`]
+    /// rays into the matching record, returning a mask with bit `i` set when
+    /// `rays[i]` hit. Meant for coherent rays, which structures such as
+    /// [`Bvh4`] can trace together; the default traces them one by one.
+    fn hit_packet<'a>(&'a self, rays: &[Ray], ray_t: Interval, recs: &mut [HitRecord<'a>]) -> u64 {
+        let mut hits = 0;
+        for (i, (r, rec)) in rays.iter().zip(recs).enumerate() {
+            if self.hit(r, ray_t, rec) {
+                hits |= 1 << i;
+            }
+        }
+        hits
+    }
+
+    /// Fills in the surface data of a hit which `hit` recorded with
+    /// [`HitRecord::defer_surface`]. Objects computing everything up front
+    /// need not implement this.
+    fn surface(&self, _r: &Ray, _rec: &mut HitRecord) {}
+
+    /// Whether the object encloses a volume, and so answers [`spans`] for
+    /// every ray.
+    ///
+    /// [`spans`]: Hittable::spans
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    /// Every span of the whole line through `r` which lies inside the object,
+    /// ordered along the ray, with complete surface data. Only closed objects
+    /// can answer this; the default returns `None`.
+    fn spans(&self, _r: &Ray) -> Option<Vec<Span<'_>>> {
+        None
+    }
+}
+
+/// A stretch of a ray inside a closed object, between the hits where it enters
+/// and exits.
+#[derive(Clone, Copy, Default)]
+pub struct Span<'a> {
+    pub enter: HitRecord<'a>,
+    pub exit: HitRecord<'a>,
+}
+
+impl<'a> Span<'a> {
+    /// Pairs up successive surface crossings of a closed object.
+    pub fn from_crossings(crossings: Vec<HitRecord<'a>>) -> Vec<Span<'a>> {
+        let mut crossings = crossings.into_iter();
+        let mut spans = Vec::new();
+        while let (Some(enter), Some(exit)) = (crossings.next(), crossings.next()) {
+            spans.push(Span { enter, exit });
+        }
+        spans
+    }
+}
+
+/// Alpha test against an optional opacity texture. Partially transparent texels
+/// pass stochastically, in proportion to their opacity, by comparing it with
+/// the sample carried by `r`.
+pub(crate) fn alpha_test(
+    alpha: &Option<Rc<dyn Texture>>,
+    u: f64,
+    v: f64,
+    p: &Point3,
+    r: &Ray,
+) -> bool {
+    match alpha {
+        None => true,
+        Some(alpha) => {
+            let opacity = alpha.value(u, v, p).luminance();
+            opacity >= 1.0 || (opacity > 0.0 && r.alpha_sample() < opacity)
+        }
+    }
 }
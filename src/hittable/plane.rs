@@ -0,0 +1,78 @@
+use crate::hit_record::HitRecord;
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::vec3::Vec3;
+
+/// The plane through `q` spanned by `u` and `v`, shared by the planar primitives.
+pub(crate) struct Plane {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    d: f64,
+    w: Vec3,
+}
+
+impl Plane {
+    pub(crate) fn new(q: Point3, u: Vec3, v: Vec3) -> Self {
+        let n = u.cross(&v);
+        let normal = n.unit_vector();
+        Self {
+            q,
+            u,
+            v,
+            normal,
+            d: normal.dot(&q.into()),
+            w: n / n.dot(&n),
+        }
+    }
+
+    pub(crate) fn u(&self) -> Vec3 {
+        self.u
+    }
+
+    pub(crate) fn v(&self) -> Vec3 {
+        self.v
+    }
+
+    /// Returns `t` and the planar coordinates `(alpha, beta)` of the hit point,
+    /// so that `r.at(t) == q + alpha * u + beta * v`.
+    pub(crate) fn intersect(&self, r: &Ray, ray_t: Interval) -> Option<(f64, f64, f64)> {
+        let denom = self.normal.dot(&r.direction());
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = (self.d - self.normal.dot(&r.origin().into())) / denom;
+        if !ray_t.contains(t) {
+            return None;
+        }
+
+        let planar_hitpoint_vector = r.at(t) - self.q;
+        let alpha = self.w.dot(&planar_hitpoint_vector.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&planar_hitpoint_vector));
+        Some((t, alpha, beta))
+    }
+
+    /// Fills `rec` for a hit at `t` with texture coordinates `(u, v)`, using the
+    /// plane axes as tangents.
+    pub(crate) fn set_hit_record<'a>(
+        &self,
+        r: &Ray,
+        t: f64,
+        (u, v): (f64, f64),
+        mat: &'a dyn Material,
+        rec: &mut HitRecord<'a>,
+    ) {
+        rec.t = t;
+        rec.p = r.at(t);
+        rec.u = u;
+        rec.v = v;
+        rec.set_material(mat);
+        rec.set_face_normal(r, self.normal);
+        rec.tangent = self.u;
+        rec.bitangent = self.v;
+    }
+}
@@ -1,79 +1,76 @@
 use crate::aabb::Aabb;
+use crate::error::{Error, Result};
 use crate::hit_record::HitRecord;
-use crate::hittable::Hittable;
+use crate::hittable::plane::Plane;
+use crate::hittable::{alpha_test, Hittable};
 use crate::interval::Interval;
 use crate::material::Material;
 use crate::point3::Point3;
 use crate::ray::Ray;
+use crate::texture::Texture;
 use crate::vec3::Vec3;
 use std::rc::Rc;
 
 pub struct Quad {
-    q: Point3,
-    u: Vec3,
-    v: Vec3,
+    plane: Plane,
     mat: Rc<dyn Material>,
     bbox: Aabb,
-    normal: Vec3,
-    d: f64,
-    w: Vec3,
+    alpha: Option<Rc<dyn Texture>>,
 }
 
 impl Quad {
-    pub fn new(q: Point3, u: Vec3, v: Vec3, mat: Rc<dyn Material>) -> Self {
-        let n = u.cross(&v);
-        let normal = n.unit_vector();
-        Self {
-            q,
-            u,
-            v,
-            mat,
-            bbox: Aabb::from_points(q, q + &u + &v).pad(),
-            normal,
-            d: normal.dot(&q.into()),
-            w: n / n.dot(&n),
+    /// Fails if `u` and `v` are parallel or either is zero.
+    pub fn new(q: Point3, u: Vec3, v: Vec3, mat: Rc<dyn Material>) -> Result<Self> {
+        if u.cross(&v).near_zero() {
+            return Err(Error::invalid("quad edges must span a plane"));
         }
+        Ok(Self {
+            plane: Plane::new(q, u, v),
+            mat,
+            bbox: Aabb::from_points(q, q + u + v).pad(),
+            alpha: None,
+        })
     }
-}
 
-impl Hittable for Quad {
-    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
-        let denom = self.normal.dot(&r.direction());
-        if denom.abs() < 1e-8 {
-            return false;
-        }
+    /// Opacity mask; hits where the mask is transparent are ignored.
+    pub fn alpha(mut self, alpha: Rc<dyn Texture>) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+}
 
-        let t = (self.d - self.normal.dot(&r.origin().into())) / denom;
-        if !ray_t.contains(t) {
-            return false;
+impl Quad {
+    /// The hit's `t` and planar coordinates, if it is inside the quad and
+    /// passes the alpha test.
+    fn intersect(&self, r: &Ray, ray_t: Interval) -> Option<(f64, f64, f64)> {
+        let (t, alpha, beta) = self.plane.intersect(r, ray_t)?;
+        if !is_interior(alpha, beta) || !alpha_test(&self.alpha, alpha, beta, &r.at(t), r) {
+            return None;
         }
+        Some((t, alpha, beta))
+    }
+}
 
-        let intersection = r.at(t);
-        let planar_hitpoint_vector = intersection - self.q;
-        let alpha = self.w.dot(&planar_hitpoint_vector.cross(&self.v));
-        let beta = self.w.dot(&self.u.cross(&planar_hitpoint_vector));
-        if !is_interior(alpha, beta, rec) {
+impl Hittable for Quad {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some((t, alpha, beta)) = self.intersect(r, ray_t) else {
             return false;
-        }
+        };
 
-        rec.t = t;
-        rec.p = intersection;
-        rec.material = Some(self.mat.clone());
-        rec.set_face_normal(r, self.normal);
+        self.plane
+            .set_hit_record(r, t, (alpha, beta), self.mat.as_ref(), rec);
 
         true
     }
 
+    fn occluded(&self, r: &Ray, ray_t: Interval) -> bool {
+        self.intersect(r, ray_t).is_some()
+    }
+
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
 }
-fn is_interior(a: f64, b: f64, rec: &mut HitRecord) -> bool {
-    if (a < 0.0) || (1.0 < a) || (b < 0.0) || (1.0 < b) {
-        false
-    } else {
-        rec.u = a;
-        rec.v = b;
-        true
-    }
+fn is_interior(a: f64, b: f64) -> bool {
+    !((a < 0.0) || (1.0 < a) || (b < 0.0) || (1.0 < b))
 }
@@ -0,0 +1,108 @@
+use crate::aabb::Aabb;
+use crate::hit_record::HitRecord;
+use crate::hittable::Hittable;
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::sdf::Sdf;
+use crate::vec3::Vec3;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+const MAX_STEPS: usize = 512;
+const EPSILON: f64 = 1e-4;
+
+/// A signed distance field, rendered by sphere tracing within `bbox`.
+pub struct SdfHittable {
+    sdf: Rc<dyn Sdf>,
+    bbox: Aabb,
+    mat: Rc<dyn Material>,
+}
+
+impl SdfHittable {
+    pub fn new(sdf: Rc<dyn Sdf>, bbox: Aabb, mat: Rc<dyn Material>) -> Self {
+        Self { sdf, bbox, mat }
+    }
+
+    fn normal(&self, p: &Point3) -> Vec3 {
+        let h = EPSILON;
+        let d = |dx: f64, dy: f64, dz: f64| {
+            self.sdf
+                .distance(&Point3::new(p.x() + dx, p.y() + dy, p.z() + dz))
+        };
+        Vec3::new(
+            d(h, 0.0, 0.0) - d(-h, 0.0, 0.0),
+            d(0.0, h, 0.0) - d(0.0, -h, 0.0),
+            d(0.0, 0.0, h) - d(0.0, 0.0, -h),
+        )
+        .unit_vector()
+    }
+
+    /// Marches along the ray and returns the first surface crossing in `ray_t`.
+    fn march(&self, r: &Ray, mut ray_t: Interval) -> Option<f64> {
+        if !self.bbox.hit(r, &mut ray_t) {
+            return None;
+        }
+
+        let length = r.direction().length();
+        let mut t = ray_t.min;
+
+        // Rays leaving the surface start within EPSILON of it, so decide which
+        // side of the surface we are marching through from a point slightly ahead.
+        let side = self
+            .sdf
+            .distance(&r.at(t + 10.0 * EPSILON / length))
+            .signum();
+        let mut left_surface = false;
+
+        for _ in 0..MAX_STEPS {
+            if t > ray_t.max {
+                return None;
+            }
+
+            let d = side * self.sdf.distance(&r.at(t));
+            if d < EPSILON {
+                if left_surface {
+                    return Some(t);
+                }
+            } else {
+                left_surface = true;
+            }
+
+            t += d.max(EPSILON) / length;
+        }
+
+        None
+    }
+}
+
+impl Hittable for SdfHittable {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some(t) = self.march(r, ray_t) else {
+            return false;
+        };
+
+        rec.t = t;
+        rec.p = r.at(t);
+        let outward_normal = self.normal(&rec.p);
+        rec.set_face_normal(r, outward_normal);
+        rec.defer_surface(self, self.mat.as_ref());
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn surface(&self, _r: &Ray, rec: &mut HitRecord) {
+        let outward_normal = rec.outward_normal();
+        rec.u = (-outward_normal.z()).atan2(outward_normal.x()) / (2.0 * PI) + 0.5;
+        rec.v = (-outward_normal.y()).acos() / PI;
+        let uvw = Onb::build_from_w(&outward_normal);
+        rec.tangent = uvw.u();
+        rec.bitangent = uvw.v();
+    }
+}
@@ -1,10 +1,12 @@
 use crate::aabb::Aabb;
+use crate::error::{Error, Result};
 use crate::hit_record::HitRecord;
-use crate::hittable::Hittable;
+use crate::hittable::{alpha_test, Hittable, Span};
 use crate::interval::Interval;
 use crate::material::Material;
 use crate::point3::Point3;
 use crate::ray::Ray;
+use crate::texture::Texture;
 use crate::vec3::Vec3;
 use std::rc::Rc;
 pub struct Sphere {
@@ -14,42 +16,59 @@ pub struct Sphere {
     center_vec: Vec3,
     is_moving: bool,
     bbox: Aabb,
+    alpha: Option<Rc<dyn Texture>>,
 }
 
 impl Sphere {
-    pub(crate) fn new(center: Point3, radius: f64, material: Rc<dyn Material>) -> Self {
+    pub fn new(center: Point3, radius: f64, material: Rc<dyn Material>) -> Result<Self> {
+        Error::check_positive("sphere radius", radius)?;
         let rvec = Vec3::new(radius, radius, radius);
-        Self {
+        Ok(Self {
             center,
             radius,
             material,
             center_vec: Vec3::default(),
             is_moving: false,
-            bbox: Aabb::from_points(center - rvec, center + &rvec),
-        }
+            bbox: Aabb::from_points(center - rvec, center + rvec),
+            alpha: None,
+        })
     }
 
-    pub(crate) fn new_moving(
+    pub fn new_moving(
         center: Point3,
         center_end: Point3,
         radius: f64,
         material: Rc<dyn Material>,
-    ) -> Self {
+    ) -> Result<Self> {
+        Error::check_positive("sphere radius", radius)?;
         let rvec = Vec3::new(radius, radius, radius);
-        let box1 = Aabb::from_points(center - rvec, center + &rvec);
-        let box2 = Aabb::from_points(center_end - rvec, center_end + &rvec);
-        Self {
+        let box1 = Aabb::from_points(center - rvec, center + rvec);
+        let box2 = Aabb::from_points(center_end - rvec, center_end + rvec);
+        Ok(Self {
             center,
             radius,
             material,
             center_vec: center_end - center,
             is_moving: true,
             bbox: Aabb::from_boxes(box1, box2),
-        }
+            alpha: None,
+        })
+    }
+
+    /// Turns the normals inward, as for a bubble of air inside a glass sphere.
+    pub fn inside_out(mut self) -> Self {
+        self.radius = -self.radius;
+        self
+    }
+
+    /// Opacity mask; hits where the mask is transparent are ignored.
+    pub fn alpha(mut self, alpha: Rc<dyn Texture>) -> Self {
+        self.alpha = Some(alpha);
+        self
     }
 
     fn center(&self, time: f64) -> Point3 {
-        self.center + &(time * self.center_vec)
+        self.center + time * self.center_vec
     }
 
     fn get_sphere_uv(p: &Point3, u: &mut f64, v: &mut f64) {
@@ -59,10 +78,22 @@ impl Sphere {
         *u = phi / (2.0 * std::f64::consts::PI);
         *v = theta / std::f64::consts::PI;
     }
+
+    fn set_tangents(&self, outward_normal: &Vec3, rec: &mut HitRecord) {
+        let around = Vec3::new(outward_normal.z(), 0.0, -outward_normal.x());
+        let direction = if around.near_zero() {
+            Vec3::new(1.0, 0.0, 0.0)
+        } else {
+            around.unit_vector()
+        };
+        rec.tangent = 2.0 * std::f64::consts::PI * self.radius * around;
+        rec.bitangent = std::f64::consts::PI * self.radius * outward_normal.cross(&direction);
+    }
 }
 
-impl Hittable for Sphere {
-    fn hit(&self, r: &Ray, ray_t: Interval, rec: &mut HitRecord) -> bool {
+impl Sphere {
+    /// The sphere's centre at the ray's time and both roots, nearest first.
+    fn roots(&self, r: &Ray) -> Option<(Point3, [f64; 2])> {
         let center = if self.is_moving {
             self.center(r.time())
         } else {
@@ -76,29 +107,94 @@ impl Hittable for Sphere {
         let discriminant = half_b * half_b - a * c;
 
         if discriminant < 0.0 {
-            return false;
+            return None;
         }
         let sqrt_d = discriminant.sqrt();
 
-        let mut root = (-half_b - sqrt_d) / a;
-        if !ray_t.surrounds(root) {
-            root = (-half_b + sqrt_d) / a;
+        Some((center, [(-half_b - sqrt_d) / a, (-half_b + sqrt_d) / a]))
+    }
+
+    /// The centre and the nearest root within `ray_t` which passes the alpha
+    /// test.
+    fn nearest_root(&self, r: &Ray, ray_t: Interval) -> Option<(Point3, f64)> {
+        let (center, roots) = self.roots(r)?;
+
+        roots.into_iter().find_map(|root| {
             if !ray_t.surrounds(root) {
-                return false;
+                return None;
             }
-        }
 
-        rec.t = root;
-        rec.p = r.at(rec.t);
+            if self.alpha.is_some() {
+                let p = r.at(root);
+                let (mut u, mut v) = (0.0, 0.0);
+                Self::get_sphere_uv(&((p - center) / self.radius).into(), &mut u, &mut v);
+                if !alpha_test(&self.alpha, u, v, &p, r) {
+                    return None;
+                }
+            }
+
+            Some((center, root))
+        })
+    }
+
+    /// Fills in the hit point and normal, leaving the rest to `set_surface`.
+    fn set_hit_point(&self, r: &Ray, center: Point3, t: f64, rec: &mut HitRecord) {
+        rec.t = t;
+        rec.p = r.at(t);
         let outward_normal = (rec.p - center) / self.radius;
         rec.set_face_normal(r, outward_normal);
+    }
+
+    fn set_surface(&self, rec: &mut HitRecord) {
+        let outward_normal = rec.outward_normal();
         Self::get_sphere_uv(&outward_normal.into(), &mut rec.u, &mut rec.v);
-        rec.material = Some(self.material.clone());
+        self.set_tangents(&outward_normal, rec);
+    }
+
+    fn set_hit_record<'a>(&'a self, r: &Ray, center: Point3, t: f64, rec: &mut HitRecord<'a>) {
+        self.set_hit_point(r, center, t, rec);
+        self.set_surface(rec);
+        rec.set_material(self.material.as_ref());
+    }
+}
 
+impl Hittable for Sphere {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some((center, root)) = self.nearest_root(r, ray_t) else {
+            return false;
+        };
+
+        // The texture coordinates and tangents cost trigonometry, and are
+        // only needed if no closer hit turns up.
+        self.set_hit_point(r, center, root, rec);
+        rec.defer_surface(self, self.material.as_ref());
         true
     }
 
+    fn occluded(&self, r: &Ray, ray_t: Interval) -> bool {
+        self.nearest_root(r, ray_t).is_some()
+    }
+
     fn bounding_box(&self) -> Aabb {
         self.bbox
     }
+
+    fn surface(&self, _r: &Ray, rec: &mut HitRecord) {
+        self.set_surface(rec);
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn spans(&self, r: &Ray) -> Option<Vec<Span<'_>>> {
+        let Some((center, [t0, t1])) = self.roots(r) else {
+            return Some(vec![]);
+        };
+
+        let mut span = Span::default();
+        self.set_hit_record(r, center, t0, &mut span.enter);
+        self.set_hit_record(r, center, t1, &mut span.exit);
+        Some(vec![span])
+    }
 }
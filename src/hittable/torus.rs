@@ -0,0 +1,205 @@
+use crate::aabb::Aabb;
+use crate::error::{Error, Result};
+use crate::hit_record::HitRecord;
+use crate::hittable::{alpha_test, Hittable, Span};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::point3::Point3;
+use crate::poly::{solve_quartic, Roots};
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vec3::Vec3;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+/// A torus around `center`, with its ring of `major_radius` perpendicular to `axis`
+/// and a tube of `minor_radius`.
+pub struct Torus {
+    center: Point3,
+    uvw: Onb,
+    major_radius: f64,
+    minor_radius: f64,
+    mat: Rc<dyn Material>,
+    bbox: Aabb,
+    alpha: Option<Rc<dyn Texture>>,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: f64,
+        minor_radius: f64,
+        mat: Rc<dyn Material>,
+    ) -> Result<Self> {
+        Error::check_positive("torus major radius", major_radius)?;
+        Error::check_positive("torus minor radius", minor_radius)?;
+        Error::check_positive("torus axis length", axis.length())?;
+        let uvw = Onb::build_from_w(&axis);
+        let tube = Vec3::new(minor_radius, minor_radius, minor_radius);
+        let ring = Aabb::from_disk(center, uvw.w(), major_radius);
+        Ok(Self {
+            center,
+            uvw,
+            major_radius,
+            minor_radius,
+            mat,
+            bbox: Aabb::from_points(
+                Point3::new(ring.x.min, ring.y.min, ring.z.min) - tube,
+                Point3::new(ring.x.max, ring.y.max, ring.z.max) + tube,
+            ),
+            alpha: None,
+        })
+    }
+
+    /// Opacity mask; hits where the mask is transparent are ignored.
+    pub fn alpha(mut self, alpha: Rc<dyn Texture>) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// All intersections of the ray with the surface, nearest first, with the
+    /// ray's origin and direction in the torus' local frame.
+    fn intersections(&self, r: &Ray) -> (Vec3, Vec3, Roots) {
+        let o = self.uvw.world_to_local(&(r.origin() - self.center));
+        let d = self.uvw.world_to_local(&r.direction());
+
+        // Solve along a unit direction from the point closest to the centre,
+        // which keeps the quartic well conditioned for distant rays.
+        let length = d.length();
+        let dir = d / length;
+        let s0 = -o.dot(&dir);
+        let p = o + s0 * dir;
+
+        let r2 = self.major_radius * self.major_radius;
+        let pd = p.dot(&dir);
+        let k = p.dot(&p) + r2 - self.minor_radius * self.minor_radius;
+        let planar_dd = dir.x() * dir.x() + dir.y() * dir.y();
+        let planar_pd = p.x() * dir.x() + p.y() * dir.y();
+        let planar_pp = p.x() * p.x() + p.y() * p.y();
+
+        let mut roots: Roots = solve_quartic([
+            k * k - 4.0 * r2 * planar_pp,
+            4.0 * pd * k - 8.0 * r2 * planar_pd,
+            2.0 * k + 4.0 * pd * pd - 4.0 * r2 * planar_dd,
+            4.0 * pd,
+            1.0,
+        ])
+        .iter()
+        .map(|s| (s + s0) / length)
+        .collect();
+
+        roots.sort_by(f64::total_cmp);
+        (o, d, roots)
+    }
+
+    /// Local outward normal at a local point.
+    fn local_normal(&self, p: &Vec3) -> Vec3 {
+        let rho = (p.x() * p.x() + p.y() * p.y()).sqrt();
+        let ring = if rho > 0.0 {
+            Vec3::new(p.x(), p.y(), 0.0) / rho
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        (p - self.major_radius * ring) / self.minor_radius
+    }
+
+    /// Texture coordinates and tangents at a local point.
+    fn local_surface(&self, p: &Vec3) -> ((f64, f64), Vec3, Vec3) {
+        let theta = p.y().atan2(p.x());
+        let rho = (p.x() * p.x() + p.y() * p.y()).sqrt();
+        let phi = p.z().atan2(rho - self.major_radius);
+
+        let around_tube = Vec3::new(
+            -phi.sin() * theta.cos(),
+            -phi.sin() * theta.sin(),
+            phi.cos(),
+        );
+        (
+            ((theta + PI) / (2.0 * PI), (phi + PI) / (2.0 * PI)),
+            2.0 * PI * Vec3::new(-p.y(), p.x(), 0.0),
+            2.0 * PI * self.minor_radius * around_tube,
+        )
+    }
+
+    /// Fills in the hit point and normal, leaving the rest to `set_surface`.
+    fn set_hit_point(&self, r: &Ray, (o, d): (Vec3, Vec3), t: f64, rec: &mut HitRecord) {
+        rec.t = t;
+        rec.p = r.at(t);
+        let normal = self.local_normal(&(o + t * d));
+        rec.set_face_normal(r, self.uvw.local_vec(&normal).unit_vector());
+    }
+
+    fn set_surface(&self, p: &Vec3, rec: &mut HitRecord) {
+        let ((u, v), tangent, bitangent) = self.local_surface(p);
+        rec.u = u;
+        rec.v = v;
+        rec.tangent = self.uvw.local_vec(&tangent);
+        rec.bitangent = self.uvw.local_vec(&bitangent);
+    }
+
+    fn set_hit_record<'a>(
+        &'a self,
+        r: &Ray,
+        (o, d): (Vec3, Vec3),
+        t: f64,
+        rec: &mut HitRecord<'a>,
+    ) {
+        self.set_hit_point(r, (o, d), t, rec);
+        self.set_surface(&(o + t * d), rec);
+        rec.set_material(self.mat.as_ref());
+    }
+}
+
+impl Hittable for Torus {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let (o, d, roots) = self.intersections(r);
+
+        for &t in roots.iter() {
+            if !ray_t.surrounds(t) {
+                continue;
+            }
+
+            if self.alpha.is_some() {
+                let ((u, v), _, _) = self.local_surface(&(o + t * d));
+                if !alpha_test(&self.alpha, u, v, &r.at(t), r) {
+                    continue;
+                }
+            }
+
+            // The texture coordinates and tangents cost trigonometry, and are
+            // only needed if no closer hit turns up.
+            self.set_hit_point(r, (o, d), t, rec);
+            rec.defer_surface(self, self.mat.as_ref());
+            return true;
+        }
+
+        false
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn surface(&self, _r: &Ray, rec: &mut HitRecord) {
+        self.set_surface(&self.uvw.world_to_local(&(rec.p - self.center)), rec);
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn spans(&self, r: &Ray) -> Option<Vec<Span<'_>>> {
+        let (o, d, roots) = self.intersections(r);
+        let crossings = roots
+            .iter()
+            .map(|&t| {
+                let mut rec = HitRecord::default();
+                self.set_hit_record(r, (o, d), t, &mut rec);
+                rec
+            })
+            .collect();
+        Some(Span::from_crossings(crossings))
+    }
+}
@@ -0,0 +1,63 @@
+use crate::aabb::Aabb;
+use crate::error::{Error, Result};
+use crate::hit_record::HitRecord;
+use crate::hittable::plane::Plane;
+use crate::hittable::{alpha_test, Hittable};
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use std::rc::Rc;
+
+pub struct Triangle {
+    plane: Plane,
+    mat: Rc<dyn Material>,
+    bbox: Aabb,
+    alpha: Option<Rc<dyn Texture>>,
+}
+
+impl Triangle {
+    /// Fails if the corners lie on one line.
+    pub fn new(a: Point3, b: Point3, c: Point3, mat: Rc<dyn Material>) -> Result<Self> {
+        if (b - a).cross(&(c - a)).near_zero() {
+            return Err(Error::invalid("triangle corners must not lie on one line"));
+        }
+        Ok(Self {
+            plane: Plane::new(a, b - a, c - a),
+            mat,
+            bbox: Aabb::from_boxes(Aabb::from_points(a, b), Aabb::from_points(a, c)).pad(),
+            alpha: None,
+        })
+    }
+
+    /// Opacity mask; hits where the mask is transparent are ignored.
+    pub fn alpha(mut self, alpha: Rc<dyn Texture>) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit<'a>(&'a self, r: &Ray, ray_t: Interval, rec: &mut HitRecord<'a>) -> bool {
+        let Some((t, alpha, beta)) = self.plane.intersect(r, ray_t) else {
+            return false;
+        };
+
+        if alpha < 0.0 || beta < 0.0 || alpha + beta > 1.0 {
+            return false;
+        }
+        if !alpha_test(&self.alpha, alpha, beta, &r.at(t), r) {
+            return false;
+        }
+
+        self.plane
+            .set_hit_record(r, t, (alpha, beta), self.mat.as_ref(), rec);
+
+        true
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
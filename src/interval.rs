@@ -1,7 +1,7 @@
 #[derive(Debug, Copy, Clone)]
 pub struct Interval {
-    pub(crate) min: f64,
-    pub(crate) max: f64,
+    pub min: f64,
+    pub max: f64,
 }
 
 impl Interval {
@@ -15,6 +15,13 @@ impl Interval {
             max: self.max.max(other.max),
         }
     }
+
+    pub fn intersection(&self, other: Interval) -> Self {
+        Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        }
+    }
 }
 
 impl Interval {
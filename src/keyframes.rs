@@ -0,0 +1,99 @@
+use crate::point3::Point3;
+use crate::vec3::Vec3;
+
+pub trait Interpolate {
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for Vec3 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        (1.0 - t) * *self + t * *other
+    }
+}
+
+impl Interpolate for Point3 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Vec3::from(*self).interpolate(&Vec3::from(*other), t).into()
+    }
+}
+
+/// How a value travels from one key to the next.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Curve {
+    /// Holds the value until the next key.
+    Step,
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Curve {
+    /// Maps the linear progress `t` in `[0, 1]` through the curve.
+    pub fn apply(&self, t: f64) -> f64 {
+        match self {
+            Curve::Step => 0.0,
+            Curve::Linear => t,
+            Curve::EaseIn => t * t,
+            Curve::EaseOut => t * (2.0 - t),
+            Curve::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Values at points in time, interpolated in between and held constant before
+/// the first and after the last key.
+#[derive(Clone, Debug)]
+pub struct Keyframes<T> {
+    keys: Vec<(f64, T, Curve)>,
+}
+
+impl<T> Default for Keyframes<T> {
+    fn default() -> Self {
+        Self { keys: Vec::new() }
+    }
+}
+
+impl<T: Interpolate + Clone> Keyframes<T> {
+    pub fn key(self, time: f64, value: T) -> Self {
+        self.key_with(time, value, Curve::Linear)
+    }
+
+    /// Adds a key whose value moves on to the next key along `curve`.
+    pub fn key_with(mut self, time: f64, value: T, curve: Curve) -> Self {
+        let index = self.keys.partition_point(|(t, _, _)| *t <= time);
+        self.keys.insert(index, (time, value, curve));
+        self
+    }
+
+    pub fn times(&self) -> impl Iterator<Item = f64> + '_ {
+        self.keys.iter().map(|(t, _, _)| *t)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// # Panics
+    /// If there are no keys.
+    pub fn at(&self, time: f64) -> T {
+        let next = self.keys.partition_point(|(t, _, _)| *t <= time);
+        if next == 0 {
+            return self.keys[0].1.clone();
+        }
+        if next == self.keys.len() {
+            return self.keys[next - 1].1.clone();
+        }
+
+        let (t0, v0, curve) = &self.keys[next - 1];
+        let (t1, v1, _) = &self.keys[next];
+        v0.interpolate(v1, curve.apply((time - t0) / (t1 - t0)))
+    }
+}
@@ -0,0 +1,36 @@
+//! A Monte Carlo path tracer.
+//!
+//! Scenes are built from [`hittable`] objects with [`material`]s and
+//! [`texture`]s, and rendered to PPM images by a [`camera::Camera`].
+
+pub mod aabb;
+pub mod animation;
+pub mod aperture;
+pub mod camera;
+pub mod color;
+pub mod distributed;
+mod error;
+pub mod filter;
+pub mod hit_record;
+pub mod hittable;
+pub mod interval;
+pub mod keyframes;
+pub mod material;
+pub mod medium;
+pub mod noise;
+mod onb;
+pub mod point3;
+mod poly;
+mod preview;
+pub mod progress;
+pub mod projection;
+pub mod ray;
+pub mod sampler;
+pub mod sdf;
+mod simd;
+mod spectrum;
+pub mod texture;
+pub mod transform;
+pub mod vec3;
+
+pub use error::{Error, Result};
@@ -1,35 +1,74 @@
 use chrono::Utc;
 
+use std::io;
+use std::net::TcpListener;
 use std::rc::Rc;
+use std::time::Duration;
 
-use rand::{thread_rng, Rng};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
 
-use crate::camera::Camera;
-use crate::color::Color;
-use crate::hittable::{BvhNode, HittableList, Quad, Sphere};
-use crate::material::{Dielectric, DiffuseLight, Lambertian, Metal};
-use crate::point3::Point3;
-use crate::ray::Ray;
-use crate::texture::{
+use rust_tracer::aabb::Aabb;
+use rust_tracer::animation::Animation;
+use rust_tracer::aperture::{MaskAperture, Polygon};
+use rust_tracer::camera::Camera;
+use rust_tracer::color::Color;
+use rust_tracer::distributed::{run_worker, Coordinator};
+use rust_tracer::filter::{
+    BoxFilter, Filter, GaussianFilter, LanczosFilter, MitchellFilter, TentFilter,
+};
+use rust_tracer::hittable::{
+    Animated, Bvh4, BvhNode, Cone, Csg, CsgOperation, Cuboid, Cylinder, Disk, Ellipse, Hittable,
+    HittableList, Quad, SdfHittable, Sphere, Torus, Triangle,
+};
+use rust_tracer::keyframes::{Curve, Keyframes};
+use rust_tracer::material::{BumpMap, Dielectric, DiffuseLight, Lambertian, Metal, NormalMap};
+use rust_tracer::medium::Ior;
+use rust_tracer::point3::Point3;
+use rust_tracer::progress::{CancellationToken, JsonLinesProgress};
+use rust_tracer::projection::{
+    Cylindrical, Equirectangular, Fisheye, LensElement, Orthographic, Perspective, Projection,
+    RealisticLens, Stereo,
+};
+use rust_tracer::sampler::{
+    HaltonSampler, IndependentSampler, Sampler, SobolSampler, StratifiedSampler,
+};
+use rust_tracer::sdf::{
+    BoxSdf, CapsuleSdf, Difference, Intersection, Mandelbulb, Onion, Repeat, Rotate, Round, Scale,
+    Sdf, SmoothDifference, SmoothIntersection, SmoothUnion, SphereSdf, TorusSdf, Translate, Union,
+};
+use rust_tracer::texture::{
     CheckerTexture, ImageTexture, MarbleTexture, NoiseTexture, SolidColor, Texture,
 };
-use crate::vec3::Vec3;
-
-mod aabb;
-mod camera;
-mod color;
-mod hit_record;
-mod hittable;
-mod interval;
-mod material;
-mod noise;
-mod point3;
-mod ray;
-mod texture;
-mod vec3;
-
-fn random_spheres() -> std::io::Result<()> {
-    let mut rng = thread_rng();
+use rust_tracer::transform::Transform;
+use rust_tracer::vec3::Vec3;
+use rust_tracer::{Error, Result};
+
+/// Renders the random spheres through the acceleration structure named by
+/// `structure`: `list`, `bvh`, `bvh4` or `bvh4-packets`, with a preview served
+/// on `preview`, a loopback address, if given.
+fn random_spheres(structure: &str, preview: Option<&str>) -> Result<()> {
+    let (mut camera, world) = random_spheres_scene(thread_rng().gen())?;
+    if let Some(address) = preview {
+        camera = camera.preview(address);
+    }
+    let file_name = format!("renders/{}.ppm", Utc::now().to_rfc2822());
+    match structure {
+        "list" => camera.render(&file_name, &world),
+        "bvh" => camera.render(&file_name, &BvhNode::from_hittable_list(world)),
+        "bvh4" => camera.render(&file_name, &Bvh4::from_hittable_list(world)),
+        "bvh4-packets" => camera
+            .packet_traversal(true)
+            .render(&file_name, &Bvh4::from_hittable_list(world)),
+        _ => Err(Error::InvalidParameter(format!(
+            "unknown acceleration structure {}",
+            structure
+        ))),
+    }
+}
+
+fn random_spheres_scene(seed: u64) -> Result<(Camera, HittableList)> {
+    let mut rng = StdRng::seed_from_u64(seed);
 
     let mut world = HittableList::default();
 
@@ -43,7 +82,7 @@ fn random_spheres() -> std::io::Result<()> {
         Point3::new(0.0, -1000.0, -1.0),
         1000.0,
         checker,
-    )));
+    )?));
 
     for a in -11..11 {
         for b in -11..11 {
@@ -56,68 +95,70 @@ fn random_spheres() -> std::io::Result<()> {
 
             if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
                 if choose_mat < 0.8 {
-                    let albedo = Color::random() * Color::random();
+                    let albedo = Color::new(rng.gen(), rng.gen(), rng.gen())
+                        * Color::new(rng.gen(), rng.gen(), rng.gen());
                     let material = Rc::new(Lambertian::new_with_color(albedo));
-                    let center2 = center + &Vec3::new(0.0, rng.gen_range(0.0..0.2), 0.0);
-                    world.add(Rc::new(Sphere::new_moving(center, center2, 0.2, material)))
+                    let center2 = center + Vec3::new(0.0, rng.gen_range(0.0..0.2), 0.0);
+                    world.add(Rc::new(Sphere::new_moving(center, center2, 0.2, material)?))
                 } else if choose_mat < 0.95 {
-                    let albedo = Color::random() * Color::random_range(0.5, 1.0);
+                    let albedo = Color::new(rng.gen(), rng.gen(), rng.gen())
+                        * Color::new(
+                            rng.gen_range(0.5..1.0),
+                            rng.gen_range(0.5..1.0),
+                            rng.gen_range(0.5..1.0),
+                        );
                     let fuzz = rng.gen_range(0.0..0.5);
-                    let material = Rc::new(Metal::new(albedo, fuzz));
-                    world.add(Rc::new(Sphere::new(center, 0.2, material)))
+                    let material = Rc::new(Metal::new(albedo, fuzz)?);
+                    world.add(Rc::new(Sphere::new(center, 0.2, material)?))
                 } else {
-                    let material = Rc::new(Dielectric::new(1.5));
-                    world.add(Rc::new(Sphere::new(center, 0.2, material.clone())));
+                    let material = Rc::new(Dielectric::new(1.5)?);
+                    world.add(Rc::new(Sphere::new(center, 0.2, material.clone())?));
                     if rng.gen_bool(0.5) {
-                        world.add(Rc::new(Sphere::new(center, -0.15, material)));
+                        world.add(Rc::new(Sphere::new(center, 0.15, material)?.inside_out()));
                     }
                 }
             }
         }
     }
 
-    let material1 = Rc::new(Dielectric::new(1.5));
+    let material1 = Rc::new(Dielectric::new(1.5)?);
     world.add(Rc::new(Sphere::new(
         Point3::new(0.0, 1.0, 0.0),
         1.0,
         material1.clone(),
-    )));
+    )?));
 
     let material2 = Rc::new(Lambertian::new_with_color(Color::new(0.4, 0.2, 0.1)));
     world.add(Rc::new(Sphere::new(
         Point3::new(-4.0, 1.0, 0.0),
         1.0,
         material2,
-    )));
+    )?));
 
-    let material3 = Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0));
+    let material3 = Rc::new(Metal::new(Color::new(0.7, 0.6, 0.5), 0.0)?);
     world.add(Rc::new(Sphere::new(
         Point3::new(4.0, 1.0, 0.0),
         1.0,
         material3.clone(),
-    )));
+    )?));
     /*
         world.add(Rc::new(Sphere::new(
             Point3::new(-100.0, 1.0, 0.0),
             20.0,
             material3,
-        )));
+        )?));
     */
-    let material4 = Rc::new(Dielectric::new(1.9));
+    let material4 = Rc::new(Dielectric::new(1.9)?);
     world.add(Rc::new(Sphere::new(
         Point3::new(8.0, 1.0, 0.0),
         1.0,
         material4.clone(),
-    )));
-    world.add(Rc::new(Sphere::new(
-        Point3::new(8.0, 1.0, 0.0),
-        -0.9,
-        material4,
-    )));
-
-    let world = BvhNode::from_hittable_list(world);
+    )?));
+    world.add(Rc::new(
+        Sphere::new(Point3::new(8.0, 1.0, 0.0), 0.9, material4)?.inside_out(),
+    ));
 
-    Camera::default()
+    let camera = Camera::default()
         .aspect_ratio(16.0 / 9.0)
         .image_width(1200)
         .samples_per_pixel(5000)
@@ -129,15 +170,12 @@ fn random_spheres() -> std::io::Result<()> {
         .vup(Vec3::new(0.0, 1.0, 0.0))
         .defocus_angle(0.6)
         .focus_dist(10.0)
-        .render(
-            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
-            &world,
-        )?;
+        .sampler(Rc::new(IndependentSampler::new(seed)));
 
-    Ok(())
+    Ok((camera, world))
 }
 
-fn two_spheres() -> std::io::Result<()> {
+fn two_spheres() -> Result<()> {
     let mut world = HittableList::default();
 
     let checker = Rc::new(CheckerTexture::new_with_colours(
@@ -150,12 +188,12 @@ fn two_spheres() -> std::io::Result<()> {
         Point3::new(0.0, -10.0, 0.0),
         10.0,
         material.clone(),
-    )));
+    )?));
     world.add(Rc::new(Sphere::new(
         Point3::new(0.0, 10.0, 0.0),
         10.0,
         material.clone(),
-    )));
+    )?));
 
     Camera::default()
         .aspect_ratio(16.0 / 9.0)
@@ -176,10 +214,10 @@ fn two_spheres() -> std::io::Result<()> {
     Ok(())
 }
 
-fn earth() {
-    let earth_texture = Rc::new(ImageTexture::new("/home/Strawby/Downloads/earthmap.jpg"));
+fn earth() -> Result<()> {
+    let earth_texture = Rc::new(ImageTexture::new("/home/Strawby/Downloads/earthmap.jpg")?);
     let earth_surface = Rc::new(Lambertian::new(earth_texture));
-    let globe = Sphere::new(Point3::new(0.0, 0.0, 0.0), 2.0, earth_surface);
+    let globe = Sphere::new(Point3::new(0.0, 0.0, 0.0), 2.0, earth_surface)?;
 
     Camera::default()
         .aspect_ratio(16.0 / 9.0)
@@ -195,11 +233,12 @@ fn earth() {
         .render(
             format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
             &globe,
-        )
-        .unwrap();
+        )?;
+
+    Ok(())
 }
 
-fn two_perlin_spheres() {
+fn two_perlin_spheres() -> Result<()> {
     let mut world = HittableList::default();
 
     let perlin_texture = Rc::new(MarbleTexture::new(4.0));
@@ -208,12 +247,12 @@ fn two_perlin_spheres() {
         Point3::new(0.0, -1000.0, 0.0),
         1000.0,
         perlin_material.clone(),
-    )));
+    )?));
     world.add(Rc::new(Sphere::new(
         Point3::new(0.0, 2.0, 0.0),
         2.0,
         perlin_material,
-    )));
+    )?));
 
     Camera::default()
         .aspect_ratio(16.0 / 9.0)
@@ -229,11 +268,12 @@ fn two_perlin_spheres() {
         .render(
             format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
             &world,
-        )
-        .unwrap();
+        )?;
+
+    Ok(())
 }
 
-fn quads() {
+fn quads() -> Result<()> {
     let mut world = HittableList::default();
 
     let red = Rc::new(Lambertian::new_with_color(Color::new(1.0, 0.2, 0.2)));
@@ -247,31 +287,31 @@ fn quads() {
         Vec3::new(0.0, 0.0, -4.0),
         Vec3::new(0.0, 4.0, 0.0),
         red,
-    )));
+    )?));
     world.add(Rc::new(Quad::new(
         Point3::new(-2.0, -2.0, 0.0),
         Vec3::new(4.0, 0.0, 0.0),
         Vec3::new(0.0, 4.0, 0.0),
         green,
-    )));
+    )?));
     world.add(Rc::new(Quad::new(
         Point3::new(3.0, -2.0, 1.0),
         Vec3::new(0.0, 0.0, 4.0),
         Vec3::new(0.0, 4.0, 0.0),
         blue,
-    )));
+    )?));
     world.add(Rc::new(Quad::new(
         Point3::new(-2.0, 3.0, 1.0),
         Vec3::new(4.0, 0.0, 0.0),
         Vec3::new(0.0, 0.0, 4.0),
         orange,
-    )));
+    )?));
     world.add(Rc::new(Quad::new(
         Point3::new(-2.0, -3.0, 5.0),
         Vec3::new(4.0, 0.0, 0.0),
         Vec3::new(0.0, 0.0, -4.0),
         teal,
-    )));
+    )?));
 
     Camera::default()
         .aspect_ratio(1.0)
@@ -287,28 +327,29 @@ fn quads() {
         .render(
             format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
             &world,
-        )
-        .unwrap();
+        )?;
+
+    Ok(())
 }
 
-fn simple_light() {
+fn simple_light() -> Result<()> {
     let mut world = HittableList::default();
 
     let perlin_texture = Rc::new(NoiseTexture::new(4.0));
     let noise_material = Rc::new(Lambertian::new(perlin_texture));
 
-    let mirror = Rc::new(Dielectric::new(1.0));
+    let mirror = Rc::new(Dielectric::new(1.0)?);
 
     world.add(Rc::new(Sphere::new(
         Point3::new(0.0, -1000.0, 0.0),
         1000.0,
         noise_material.clone(),
-    )));
+    )?));
     world.add(Rc::new(Sphere::new(
         Point3::new(0.0, 2.0, 0.0),
         2.0,
         noise_material,
-    )));
+    )?));
 
     let difflight = Rc::new(DiffuseLight::new(Rc::<SolidColor>::new(
         Color::new(4.0, 4.0, 4.0).into(),
@@ -318,7 +359,7 @@ fn simple_light() {
         Vec3::new(2.0, 0.0, 0.0),
         Vec3::new(0.0, 2.0, 0.0),
         difflight,
-    )));
+    )?));
 
     // let world = BvhNode::from_hittable_list(world);
 
@@ -336,11 +377,1194 @@ fn simple_light() {
         .render(
             format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
             &world,
-        )
-        .unwrap();
+        )?;
+
+    Ok(())
+}
+fn nested_media() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(Lambertian::new(Rc::new(CheckerTexture::new_with_colours(
+        0.5,
+        Color::new(0.2, 0.3, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ))));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    )?));
+
+    let glass = Rc::new(
+        Dielectric::new(1.5)?
+            .absorption(Color::new(0.05, 0.0, 0.05))
+            .priority(3),
+    );
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        glass.clone(),
+    )?));
+    world.add(Rc::new(
+        Sphere::new(Point3::new(0.0, 1.0, 0.0), 0.9, glass)?.inside_out(),
+    ));
+
+    let water = Rc::new(
+        Dielectric::new(1.33)?
+            .absorption(Color::new(0.6, 0.2, 0.05))
+            .priority(1),
+    );
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        0.92,
+        water,
+    )?));
+
+    let ice = Rc::new(Dielectric::new(1.31)?.priority(2));
+    world.add(Rc::new(Sphere::new(Point3::new(0.2, 1.3, 0.1), 0.3, ice)?));
+
+    Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vfov(20.0)
+        .lookfrom(Point3::new(8.0, 2.0, 3.0))
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
+}
+fn dispersion() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(Lambertian::new_with_color(Color::new(0.8, 0.8, 0.8)));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    )?));
+
+    let diamond = Rc::new(Dielectric::from_ior(Ior::DIAMOND));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        diamond,
+    )?));
+
+    let flint = Rc::new(Dielectric::cauchy(1.67, 0.0743));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-2.5, 1.0, 0.0),
+        1.0,
+        flint,
+    )?));
+
+    let light = Rc::new(DiffuseLight::from_color(Color::new(15.0, 15.0, 15.0)));
+    world.add(Rc::new(Quad::new(
+        Point3::new(-1.5, 5.0, -0.25),
+        Vec3::new(0.5, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 0.5),
+        light,
+    )?));
+
+    Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(1000)
+        .max_depth(50)
+        .background(Color::new(0.05, 0.05, 0.08))
+        .spectral(true)
+        .vfov(30.0)
+        .lookfrom(Point3::new(2.0, 6.0, 10.0))
+        .lookat(Point3::new(-1.0, 0.5, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
+}
+
+fn bump_mapping() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(BumpMap::new(
+        Rc::new(Lambertian::new_with_color(Color::new(0.8, 0.8, 0.8))),
+        Rc::new(NoiseTexture::new(4.0)),
+        0.05,
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    )?));
+
+    let metal = Rc::new(BumpMap::new(
+        Rc::new(Metal::new(Color::new(0.8, 0.6, 0.5), 0.0)?),
+        Rc::new(MarbleTexture::new(4.0)),
+        0.005,
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 2.0, 0.0),
+        2.0,
+        metal,
+    )?));
+
+    let tiles = Rc::new(BumpMap::new(
+        Rc::new(Lambertian::new_with_color(Color::new(0.2, 0.4, 0.8))),
+        Rc::new(CheckerTexture::new_with_colours(
+            0.5,
+            Color::new(0.0, 0.0, 0.0),
+            Color::new(1.0, 1.0, 1.0),
+        )),
+        0.01,
+    ));
+    world.add(Rc::new(Quad::new(
+        Point3::new(-3.0, 0.0, -3.0),
+        Vec3::new(0.0, 0.0, 6.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        tiles,
+    )?));
+
+    Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vfov(25.0)
+        .lookfrom(Point3::new(13.0, 3.0, 4.0))
+        .lookat(Point3::new(0.0, 1.5, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
+}
+
+fn normal_mapping() -> Result<()> {
+    let bricks = Rc::new(NormalMap::new(
+        Rc::new(Lambertian::new(Rc::new(ImageTexture::new(
+            "textures/bricks_color.jpg",
+        )?))),
+        Rc::new(ImageTexture::new("textures/bricks_normal.jpg")?),
+        1.0,
+    ));
+    let wall = Quad::new(
+        Point3::new(-2.0, -2.0, 0.0),
+        Vec3::new(4.0, 0.0, 0.0),
+        Vec3::new(0.0, 4.0, 0.0),
+        bricks,
+    )?;
+
+    Camera::default()
+        .aspect_ratio(1.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vfov(40.0)
+        .lookfrom(Point3::new(3.0, 1.0, 6.0))
+        .lookat(Point3::new(0.0, 0.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &wall,
+        )?;
+
+    Ok(())
+}
+
+fn cutouts() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(Lambertian::new_with_color(Color::new(0.5, 0.5, 0.5)));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    )?));
+
+    let fence = Rc::new(CheckerTexture::new_with_colours(
+        0.25,
+        Color::new(0.0, 0.0, 0.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    let wood = Rc::new(Lambertian::new_with_color(Color::new(0.6, 0.4, 0.2)));
+    world.add(Rc::new(
+        Quad::new(
+            Point3::new(-3.0, 0.0, 1.5),
+            Vec3::new(6.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            wood,
+        )?
+        .alpha(fence),
+    ));
+
+    let perforated = Rc::new(Lambertian::new_with_color(Color::new(0.2, 0.4, 0.8)));
+    world.add(Rc::new(
+        Sphere::new(Point3::new(-1.2, 1.0, 0.0), 1.0, perforated)?
+            .alpha(Rc::new(MarbleTexture::new(6.0))),
+    ));
+
+    let ghost = Rc::new(Lambertian::new_with_color(Color::new(0.9, 0.9, 0.9)));
+    world.add(Rc::new(
+        Sphere::new(Point3::new(1.2, 1.0, 0.0), 1.0, ghost)?
+            .alpha(Rc::new(SolidColor::from_rgb(0.5, 0.5, 0.5))),
+    ));
+
+    Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vfov(30.0)
+        .lookfrom(Point3::new(0.0, 2.0, 10.0))
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
+}
+
+fn primitives() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(Lambertian::new(Rc::new(CheckerTexture::new_with_colours(
+        0.5,
+        Color::new(0.2, 0.3, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ))));
+    world.add(Rc::new(Quad::new(
+        Point3::new(-10.0, 0.0, -10.0),
+        Vec3::new(20.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 20.0),
+        ground,
+    )?));
+
+    let red = Rc::new(Lambertian::new_with_color(Color::new(0.8, 0.2, 0.2)));
+    let green = Rc::new(Lambertian::new_with_color(Color::new(0.2, 0.8, 0.2)));
+    let blue = Rc::new(Lambertian::new_with_color(Color::new(0.2, 0.2, 0.8)));
+    let gold = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1)?);
+    let glass = Rc::new(Dielectric::new(1.5)?);
+
+    world.add(Rc::new(Cylinder::new(
+        Point3::new(-3.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        0.7,
+        true,
+        red.clone(),
+    )?));
+    world.add(Rc::new(Cylinder::new(
+        Point3::new(-3.0, 0.0, 2.5),
+        Vec3::new(0.5, 1.5, 0.0),
+        0.5,
+        false,
+        green.clone(),
+    )?));
+    world.add(Rc::new(Cone::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.5, 0.0),
+        1.0,
+        true,
+        blue.clone(),
+    )?));
+    world.add(Rc::new(Torus::new(
+        Point3::new(3.0, 1.2, 0.0),
+        Vec3::new(0.0, 1.0, 1.0),
+        0.9,
+        0.3,
+        gold,
+    )?));
+    world.add(Rc::new(Triangle::new(
+        Point3::new(-1.5, 0.0, 3.0),
+        Point3::new(0.5, 0.0, 3.0),
+        Point3::new(-0.5, 1.8, 3.0),
+        glass,
+    )?));
+    world.add(Rc::new(Disk::new(
+        Point3::new(1.5, 1.0, 3.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        0.8,
+        red,
+    )?));
+    world.add(Rc::new(Ellipse::new(
+        Point3::new(3.8, 1.0, 2.8),
+        Vec3::new(0.9, 0.0, 0.0),
+        Vec3::new(0.0, 0.5, 0.3),
+        green,
+    )));
+
+    let light = Rc::new(DiffuseLight::from_color(Color::new(4.0, 4.0, 4.0)));
+    world.add(Rc::new(Quad::new(
+        Point3::new(-2.0, 6.0, -2.0),
+        Vec3::new(4.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 4.0),
+        light,
+    )?));
+
+    let world = BvhNode::from_hittable_list(world);
+
+    Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.35, 0.40, 0.50))
+        .vfov(40.0)
+        .lookfrom(Point3::new(0.0, 4.0, 10.0))
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
 }
-fn main() -> std::io::Result<()> {
-    simple_light();
+
+fn signed_distance_fields() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(Lambertian::new_with_color(Color::new(0.5, 0.5, 0.5)));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    )?));
+
+    let blob: Rc<dyn Sdf> = Rc::new(SmoothUnion::new(
+        Rc::new(Round::new(
+            Rc::new(BoxSdf::new(Vec3::new(0.6, 0.6, 0.6))),
+            0.15,
+        )),
+        Rc::new(Translate::new(
+            Rc::new(SphereSdf::new(0.6)),
+            Vec3::new(0.0, 0.9, 0.0),
+        )),
+        0.3,
+    ));
+    world.add(Rc::new(SdfHittable::new(
+        Rc::new(Translate::new(blob, Vec3::new(-2.5, 0.75, 0.0))),
+        Aabb::from_points(Point3::new(-3.5, -0.1, -1.0), Point3::new(-1.5, 2.4, 1.0)),
+        Rc::new(Lambertian::new_with_color(Color::new(0.8, 0.3, 0.2))),
+    )));
+
+    world.add(Rc::new(SdfHittable::new(
+        Rc::new(Translate::new(
+            Rc::new(Scale::new(Rc::new(Mandelbulb::new(8.0, 12)), 0.9)),
+            Vec3::new(0.0, 1.2, 0.0),
+        )),
+        Aabb::from_points(Point3::new(-1.3, -0.1, -1.3), Point3::new(1.3, 2.5, 1.3)),
+        Rc::new(Metal::new(Color::new(0.8, 0.7, 0.5), 0.2)?),
+    )));
+
+    let ring: Rc<dyn Sdf> = Rc::new(Difference::new(
+        Rc::new(Rotate::new(
+            Rc::new(TorusSdf::new(0.7, 0.25)),
+            Vec3::new(1.0, 0.0, 0.0),
+            90.0,
+        )),
+        Rc::new(Repeat::new(
+            Rc::new(SphereSdf::new(0.1)),
+            Vec3::new(0.3, 0.3, 0.3),
+        )),
+    ));
+    world.add(Rc::new(SdfHittable::new(
+        Rc::new(Translate::new(ring, Vec3::new(2.5, 1.0, 0.0))),
+        Aabb::from_points(Point3::new(1.5, -0.1, -0.4), Point3::new(3.5, 2.1, 0.4)),
+        Rc::new(Lambertian::new_with_color(Color::new(0.2, 0.4, 0.8))),
+    )));
+
+    let carved: Rc<dyn Sdf> = Rc::new(SmoothDifference::new(
+        Rc::new(SmoothIntersection::new(
+            Rc::new(SphereSdf::new(0.5)),
+            Rc::new(BoxSdf::new(Vec3::new(0.4, 0.4, 0.4))),
+            0.05,
+        )),
+        Rc::new(Union::new(
+            Rc::new(CapsuleSdf::new(
+                Point3::new(-1.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                0.2,
+            )),
+            Rc::new(CapsuleSdf::new(
+                Point3::new(0.0, 0.0, -1.0),
+                Point3::new(0.0, 0.0, 1.0),
+                0.2,
+            )),
+        )),
+        0.05,
+    ));
+    world.add(Rc::new(SdfHittable::new(
+        Rc::new(Translate::new(carved, Vec3::new(-1.0, 0.5, 2.0))),
+        Aabb::from_points(Point3::new(-1.6, -0.1, 1.4), Point3::new(-0.4, 1.1, 2.6)),
+        Rc::new(Lambertian::new_with_color(Color::new(0.3, 0.7, 0.3))),
+    )));
+
+    let bowl: Rc<dyn Sdf> = Rc::new(Intersection::new(
+        Rc::new(Onion::new(Rc::new(SphereSdf::new(0.5)), 0.03)),
+        Rc::new(Translate::new(
+            Rc::new(BoxSdf::new(Vec3::new(0.6, 0.4, 0.6))),
+            Vec3::new(0.0, -0.3, 0.0),
+        )),
+    ));
+    world.add(Rc::new(SdfHittable::new(
+        Rc::new(Translate::new(bowl, Vec3::new(1.0, 0.55, 2.0))),
+        Aabb::from_points(Point3::new(0.4, -0.1, 1.4), Point3::new(1.6, 0.7, 2.6)),
+        Rc::new(Dielectric::new(1.5)?),
+    )));
+
+    let world = BvhNode::from_hittable_list(world);
+
+    Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vfov(35.0)
+        .lookfrom(Point3::new(0.0, 3.0, 8.0))
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
+}
+
+fn constructive_solid_geometry() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(Lambertian::new_with_color(Color::new(0.5, 0.5, 0.5)));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    )?));
+
+    let red = Rc::new(Lambertian::new_with_color(Color::new(0.8, 0.2, 0.2)));
+    let blue = Rc::new(Lambertian::new_with_color(Color::new(0.2, 0.3, 0.8)));
+    let green = Rc::new(Lambertian::new_with_color(Color::new(0.2, 0.7, 0.3)));
+    let glass = Rc::new(Dielectric::new(1.5)?);
+
+    let center = Point3::new(-2.2, 1.0, 0.0);
+    let rounded_cube = Rc::new(Csg::intersection(
+        Rc::new(Cuboid::new(
+            center - Vec3::new(0.75, 0.75, 0.75),
+            center + Vec3::new(0.75, 0.75, 0.75),
+            red,
+        )),
+        Rc::new(Sphere::new(center, 1.0, blue.clone())?),
+    )?);
+    let mut drills = HittableList::default();
+    for axis in [
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+        Vec3::new(0.0, 0.0, 2.0),
+    ] {
+        drills.add(Rc::new(Cylinder::new(
+            center - 0.5 * axis,
+            axis,
+            0.45,
+            true,
+            green.clone(),
+        )?));
+    }
+    let mut drills = drills.objects.into_iter();
+    let first = drills.next().unwrap();
+    let drills = drills.try_fold(first, |a, b| -> Result<Rc<dyn Hittable>> {
+        Ok(Rc::new(Csg::new(CsgOperation::Union, a, b)?))
+    })?;
+    world.add(Rc::new(Csg::difference(rounded_cube, drills)?));
+
+    world.add(Rc::new(Csg::intersection(
+        Rc::new(Sphere::new(
+            Point3::new(0.0, 1.0, -1.2),
+            1.5,
+            glass.clone(),
+        )?),
+        Rc::new(Sphere::new(Point3::new(0.0, 1.0, 1.2), 1.5, glass)?),
+    )?));
+
+    world.add(Rc::new(Csg::difference(
+        Rc::new(Cuboid::new(
+            Point3::new(1.5, 0.0, -0.75),
+            Point3::new(3.0, 1.5, 0.75),
+            blue,
+        )),
+        Rc::new(Sphere::new(Point3::new(2.25, 1.5, 0.0), 0.9, green)?),
+    )?));
+
+    let world = BvhNode::from_hittable_list(world);
+
+    Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vfov(35.0)
+        .lookfrom(Point3::new(1.0, 4.0, 8.0))
+        .lookat(Point3::new(0.0, 0.8, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
+}
+
+fn keyframed_motion() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let checker = Rc::new(CheckerTexture::new_with_colours(
+        0.5,
+        Color::new(0.2, 0.3, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(checker)),
+    )?));
+
+    let red = Rc::new(Lambertian::new_with_color(Color::new(0.8, 0.2, 0.2)));
+    let blue = Rc::new(Lambertian::new_with_color(Color::new(0.2, 0.3, 0.8)));
+    let gold = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1)?);
+
+    let spinning_cube = Cuboid::new(
+        Point3::new(-0.6, -0.6, -0.6),
+        Point3::new(0.6, 0.6, 0.6),
+        red,
+    );
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    world.add(Rc::new(Animated::new(
+        Rc::new(spinning_cube),
+        Keyframes::default()
+            .key(
+                0.0,
+                Transform::default().translate(Vec3::new(-2.0, 0.6, 0.0)),
+            )
+            .key(
+                1.0,
+                Transform::default()
+                    .rotate(up, 60.0)
+                    .translate(Vec3::new(-2.0, 0.6, 0.0)),
+            ),
+    )?));
+
+    world.add(Rc::new(Animated::new(
+        Rc::new(Sphere::new(Point3::new(0.0, 0.0, 0.0), 0.6, blue)?),
+        Keyframes::default()
+            .key(
+                0.0,
+                Transform::default().translate(Vec3::new(0.0, 0.6, 0.0)),
+            )
+            .key(
+                0.5,
+                Transform::default()
+                    .scale(Vec3::new(1.0, 0.6, 1.0))
+                    .translate(Vec3::new(0.0, 0.36, 0.0)),
+            )
+            .key(
+                1.0,
+                Transform::default().translate(Vec3::new(0.0, 1.2, 0.0)),
+            ),
+    )?));
+
+    world.add(Rc::new(Animated::new(
+        Rc::new(Torus::new(Point3::new(0.0, 0.0, 0.0), up, 0.6, 0.2, gold)?),
+        Keyframes::default()
+            .key(
+                0.0,
+                Transform::default().translate(Vec3::new(1.6, 0.8, 0.0)),
+            )
+            .key(
+                1.0,
+                Transform::default()
+                    .rotate(Vec3::new(1.0, 0.0, 0.0), 90.0)
+                    .translate(Vec3::new(2.4, 0.8, 0.0)),
+            ),
+    )?));
+
+    let world = BvhNode::from_hittable_list(world);
+
+    Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vfov(30.0)
+        .lookfrom(Point3::new(0.0, 3.0, 9.0))
+        .lookat(Point3::new(0.0, 0.6, 0.0))
+        .vup(up)
+        .defocus_angle(0.0)
+        .shutter(0.0, 0.5)
+        .motion(
+            Keyframes::default()
+                .key(0.0, Transform::default())
+                .key(1.0, Transform::default().rotate(up, 0.5)),
+        )
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
+}
+
+fn turntable() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let checker = Rc::new(CheckerTexture::new_with_colours(
+        0.5,
+        Color::new(0.2, 0.3, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(checker)),
+    )?));
+
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    let red = Rc::new(Lambertian::new_with_color(Color::new(0.8, 0.2, 0.2)));
+    let gold = Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.1)?);
+    world.add(Rc::new(Sphere::new(
+        Point3::new(-2.5, 0.5, -2.0),
+        0.5,
+        Rc::new(Dielectric::new(1.5)?),
+    )?));
+    world.add(Rc::new(Animated::new(
+        Rc::new(Cuboid::new(
+            Point3::new(-0.7, -0.7, -0.7),
+            Point3::new(0.7, 0.7, 0.7),
+            red,
+        )),
+        Keyframes::default()
+            .key_with(
+                0.0,
+                Transform::default().translate(Vec3::new(0.0, 0.7, 0.0)),
+                Curve::EaseInOut,
+            )
+            .key_with(
+                1.0,
+                Transform::default()
+                    .rotate(up, 90.0)
+                    .translate(Vec3::new(0.0, 0.7, 0.0)),
+                Curve::Step,
+            )
+            .key(
+                1.5,
+                Transform::default()
+                    .rotate(up, 90.0)
+                    .translate(Vec3::new(0.0, 1.7, 0.0)),
+            ),
+    )?));
+    world.add(Rc::new(Torus::new(
+        Point3::new(2.0, 0.2, 1.5),
+        up,
+        0.6,
+        0.2,
+        gold,
+    )?));
+
+    let world = BvhNode::from_hittable_list(world);
+
+    let orbit = (0..=8).fold(Keyframes::default(), |keys, i| {
+        let angle = i as f64 * std::f64::consts::PI / 4.0;
+        keys.key(
+            i as f64 * 2.0 / 8.0,
+            Point3::new(8.0 * angle.sin(), 3.0, 8.0 * angle.cos()),
+        )
+    });
+
+    let camera = Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vup(up)
+        .defocus_angle(1.0);
+
+    Animation::new(0..48, 24.0)?
+        .skip_existing(true)
+        .lookfrom(orbit)
+        .lookat(Keyframes::default().key(0.0, Point3::new(0.0, 0.7, 0.0)))
+        .vfov(
+            Keyframes::default()
+                .key_with(0.0, 35.0, Curve::EaseInOut)
+                .key(2.0, 25.0),
+        )
+        .focus_dist(
+            Keyframes::default()
+                .key_with(0.0, 8.0, Curve::EaseInOut)
+                .key(2.0, 10.0),
+        )
+        .render(&camera, "renders/turntable_", &world)?;
+
+    Ok(())
+}
+
+fn projections() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let checker = Rc::new(CheckerTexture::new_with_colours(
+        0.5,
+        Color::new(0.2, 0.3, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(checker)),
+    )?));
+
+    for i in 0..8 {
+        let angle = i as f64 * std::f64::consts::PI / 4.0;
+        let albedo = Color::new(
+            0.5 + 0.4 * angle.cos(),
+            0.5 + 0.4 * angle.sin(),
+            0.5 - 0.4 * angle.cos(),
+        );
+        world.add(Rc::new(Sphere::new(
+            Point3::new(4.0 * angle.sin(), 0.7, -4.0 * angle.cos()),
+            0.7,
+            Rc::new(Lambertian::new_with_color(albedo)),
+        )?));
+    }
+    world.add(Rc::new(Cuboid::new(
+        Point3::new(-0.5, 0.0, -2.5),
+        Point3::new(0.5, 1.0, -1.5),
+        Rc::new(Metal::new(Color::new(0.8, 0.8, 0.9), 0.0)?),
+    )));
+
+    let world = BvhNode::from_hittable_list(world);
+
+    let camera = Camera::default()
+        .image_width(400)
+        .samples_per_pixel(50)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .lookfrom(Point3::new(0.0, 1.2, 0.0))
+        .lookat(Point3::new(0.0, 0.8, -4.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0);
+
+    let projections: [(&str, f64, Rc<dyn Projection>); 5] = [
+        ("orthographic", 16.0 / 9.0, Rc::new(Orthographic::new(2.0)?)),
+        ("fisheye", 1.0, Rc::new(Fisheye::new(180.0)?)),
+        ("equirectangular", 2.0, Rc::new(Equirectangular)),
+        ("cylindrical", 3.0, Rc::new(Cylindrical::new(240.0)?)),
+        (
+            "stereo",
+            2.0 * 4.0 / 3.0,
+            Rc::new(Stereo::new(Rc::new(Perspective::new(60.0)?), 0.3)),
+        ),
+    ];
+    for (name, aspect_ratio, projection) in projections {
+        camera
+            .clone()
+            .aspect_ratio(aspect_ratio)
+            .projection(projection)
+            .render(
+                format!("renders/{} {}.ppm", Utc::now().to_rfc2822(), name).as_str(),
+                &world,
+            )?;
+    }
+
+    Ok(())
+}
+
+fn bokeh() -> Result<()> {
+    let mut world = HittableList::default();
+
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new_with_color(Color::new(0.3, 0.3, 0.3))),
+    )?));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        Rc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.05)?),
+    )?));
+
+    let mut rng = thread_rng();
+    for _ in 0..60 {
+        let light = Rc::new(DiffuseLight::new(Rc::<SolidColor>::new(
+            Color::new(
+                rng.gen_range(2.0..8.0),
+                rng.gen_range(2.0..8.0),
+                rng.gen_range(2.0..8.0),
+            )
+            .into(),
+        )));
+        world.add(Rc::new(Sphere::new(
+            Point3::new(
+                rng.gen_range(-12.0..12.0),
+                rng.gen_range(0.5..8.0),
+                rng.gen_range(-30.0..-15.0),
+            ),
+            0.1,
+            light,
+        )?));
+    }
+
+    let world = BvhNode::from_hittable_list(world);
+
+    let camera = Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .background(Color::new(0.02, 0.02, 0.05))
+        .vfov(30.0)
+        .lookfrom(Point3::new(0.0, 1.5, 6.0))
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(3.0)
+        .focus_dist(6.0);
+
+    camera
+        .clone()
+        .aperture(Rc::new(Polygon::new(6, 15.0)?))
+        .cat_eye(0.8)
+        .render(
+            format!("renders/{} hexagonal.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+    camera
+        .clone()
+        .aperture(Rc::new(MaskAperture::new("textures/aperture_star.png")?))
+        .render(
+            format!("renders/{} mask.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+    camera.anamorphic(2.0).render(
+        format!("renders/{} anamorphic.ppm", Utc::now().to_rfc2822()).as_str(),
+        &world,
+    )?;
+
+    Ok(())
+}
+
+fn realistic_lens() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let checker = Rc::new(CheckerTexture::new_with_colours(
+        0.25,
+        Color::new(0.2, 0.3, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Rc::new(Lambertian::new(checker)),
+    )?));
+
+    for i in 0..6 {
+        let albedo = Color::new(0.9 - 0.15 * i as f64, 0.3, 0.2 + 0.15 * i as f64);
+        world.add(Rc::new(Sphere::new(
+            Point3::new(-0.8 + 0.5 * i as f64, 0.25, -1.5 - 1.5 * i as f64),
+            0.25,
+            Rc::new(Lambertian::new_with_color(albedo)),
+        )?));
+    }
+
+    let world = BvhNode::from_hittable_list(world);
+
+    let camera = Camera::default()
+        .aspect_ratio(3.0 / 2.0)
+        .image_width(400)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .lookfrom(Point3::new(0.0, 0.6, 1.0))
+        .lookat(Point3::new(0.0, 0.3, -4.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .lens(RealisticLens::double_gauss_50mm());
+
+    let singlet = RealisticLens::new(vec![
+        LensElement {
+            curvature_radius: 0.0,
+            thickness: 5.0,
+            ior: 0.0,
+            aperture: 12.0,
+        },
+        LensElement {
+            curvature_radius: 51.7,
+            thickness: 4.0,
+            ior: 1.517,
+            aperture: 20.0,
+        },
+        LensElement {
+            curvature_radius: -51.7,
+            thickness: 48.0,
+            ior: 0.0,
+            aperture: 20.0,
+        },
+    ])?;
+    camera.clone().lens(singlet).focus_dist(4.0).render(
+        format!("renders/{} singlet.ppm", Utc::now().to_rfc2822()).as_str(),
+        &world,
+    )?;
+
+    for focus_dist in [2.5, 8.0] {
+        camera.clone().focus_dist(focus_dist).render(
+            format!(
+                "renders/{} focus {}.ppm",
+                Utc::now().to_rfc2822(),
+                focus_dist
+            )
+            .as_str(),
+            &world,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn filters() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let checker = Rc::new(CheckerTexture::new_with_colours(
+        0.2,
+        Color::new(0.05, 0.05, 0.05),
+        Color::new(0.95, 0.95, 0.95),
+    ));
+    world.add(Rc::new(Quad::new(
+        Point3::new(-50.0, 0.0, 5.0),
+        Vec3::new(100.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, -100.0),
+        Rc::new(DiffuseLight::new(checker)),
+    )?));
+
+    let camera = Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(16)
+        .max_depth(2)
+        .background(Color::new(0.0, 0.0, 0.0))
+        .vfov(40.0)
+        .lookfrom(Point3::new(0.0, 1.0, 2.0))
+        .lookat(Point3::new(0.0, 0.0, -10.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0);
+
+    let filters: [(&str, Rc<dyn Filter>); 5] = [
+        ("box", Rc::new(BoxFilter::new(0.5)?)),
+        ("tent", Rc::new(TentFilter::new(1.0)?)),
+        ("gaussian", Rc::new(GaussianFilter::new(1.5, 0.5)?)),
+        (
+            "mitchell",
+            Rc::new(MitchellFilter::new(2.0, 1.0 / 3.0, 1.0 / 3.0)?),
+        ),
+        ("lanczos", Rc::new(LanczosFilter::new(2.0, 2.0)?)),
+    ];
+    for (name, filter) in filters {
+        camera.clone().filter(filter).render(
+            format!("renders/{} {}.ppm", Utc::now().to_rfc2822(), name).as_str(),
+            &world,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn samplers() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(Lambertian::new(Rc::<SolidColor>::new(
+        Color::new(0.5, 0.5, 0.5).into(),
+    )));
+    let red = Rc::new(Lambertian::new(Rc::<SolidColor>::new(
+        Color::new(0.65, 0.05, 0.05).into(),
+    )));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    )?));
+    world.add(Rc::new(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, red)?));
+
+    let difflight = Rc::new(DiffuseLight::new(Rc::<SolidColor>::new(
+        Color::new(15.0, 15.0, 15.0).into(),
+    )));
+    world.add(Rc::new(Quad::new(
+        Point3::new(-0.5, 3.0, -0.5),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        difflight,
+    )?));
+
+    let camera = Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(400)
+        .samples_per_pixel(16)
+        .max_depth(8)
+        .background(Color::new(0.02, 0.02, 0.03))
+        .vfov(30.0)
+        .lookfrom(Point3::new(0.0, 2.0, 8.0))
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(2.0)
+        .focus_dist(8.0);
+
+    let samplers: [(&str, Rc<dyn Sampler>); 4] = [
+        ("independent", Rc::new(IndependentSampler::new(7))),
+        ("stratified", Rc::new(StratifiedSampler::new(7))),
+        ("halton", Rc::new(HaltonSampler::new(7))),
+        ("sobol", Rc::new(SobolSampler::new(7))),
+    ];
+    for (name, sampler) in samplers {
+        camera.clone().sampler(sampler).render(
+            format!("renders/{} {}.ppm", Utc::now().to_rfc2822(), name).as_str(),
+            &world,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn crop_region() -> Result<()> {
+    let mut world = HittableList::default();
+
+    let ground = Rc::new(Lambertian::new(Rc::new(CheckerTexture::new_with_colours(
+        0.5,
+        Color::new(0.2, 0.3, 0.1),
+        Color::new(0.9, 0.9, 0.9),
+    ))));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    )?));
+
+    let glass = Rc::new(Dielectric::new(1.5)?.absorption(Color::new(0.05, 0.0, 0.05)));
+    world.add(Rc::new(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        glass,
+    )?));
+
+    let camera = Camera::default()
+        .aspect_ratio(16.0 / 9.0)
+        .image_width(1200)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .background(Color::new(0.70, 0.80, 1.00))
+        .vfov(20.0)
+        .lookfrom(Point3::new(8.0, 2.0, 3.0))
+        .lookat(Point3::new(0.0, 1.0, 0.0))
+        .vup(Vec3::new(0.0, 1.0, 0.0))
+        .defocus_angle(0.0)
+        .crop(380..820, 120..560);
+
+    camera.clone().render(
+        format!("renders/{} crop.ppm", Utc::now().to_rfc2822()).as_str(),
+        &world,
+    )?;
+    camera
+        .clone()
+        .crop_border(Color::new(1.0, 0.0, 1.0))
+        .render(
+            format!("renders/{} canvas.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    camera.samples_per_pixel(4).debug_pixel(600, 340, &world)?;
+
+    Ok(())
+}
+
+fn time_limited() -> Result<()> {
+    let (camera, world) = random_spheres_scene(42)?;
+
+    // Stop after a minute and keep whatever has been rendered by then.
+    let token = CancellationToken::new();
+    {
+        let token = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(60));
+            token.cancel();
+        });
+    }
+
+    camera
+        .samples_per_pixel(100)
+        .progress(Rc::new(JsonLinesProgress::new(io::stderr())))
+        .cancellation(token)
+        .render(
+            format!("renders/{}.ppm", Utc::now().to_rfc2822()).as_str(),
+            &world,
+        )?;
+
+    Ok(())
+}
+
+/// The scenes which can be rendered across worker processes.
+fn distributed_scene(name: &str, seed: u64) -> Option<(Camera, HittableList)> {
+    match name {
+        "random_spheres" => {
+            let (camera, world) = random_spheres_scene(seed).ok()?;
+            let mut bvh = HittableList::default();
+            bvh.add(Rc::new(BvhNode::from_hittable_list(world)));
+            Some((camera, bvh))
+        }
+        _ => None,
+    }
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args[..] {
+        ["coordinator", address, scene, seed, output] => {
+            let seed = seed
+                .parse()
+                .map_err(|e| Error::InvalidParameter(format!("seed {}: {}", seed, e)))?;
+            let listener = TcpListener::bind(address)?;
+            Coordinator::new(scene, seed).render(listener, distributed_scene, output)?;
+        }
+        ["worker", address] => run_worker(address, distributed_scene)?,
+        ["random_spheres", structure] => random_spheres(structure, None)?,
+        ["random_spheres", structure, preview] => random_spheres(structure, Some(preview))?,
+        [] => simple_light()?,
+        _ => {
+            eprintln!("usage: rust_tracer [coordinator <address> <scene> <seed> <output.ppm> | worker <address> | random_spheres <list|bvh|bvh4|bvh4-packets> [<preview address>]]");
+            return Err(Error::InvalidParameter(
+                "unrecognised arguments".to_string(),
+            ));
+        }
+    }
 
     Ok(())
 }
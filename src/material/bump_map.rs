@@ -0,0 +1,89 @@
+use crate::color::Color;
+use crate::hit_record::HitRecord;
+use crate::material::Material;
+use crate::medium::Medium;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::texture::Texture;
+use std::rc::Rc;
+
+const DELTA: f64 = 0.0005;
+
+/// Perturbs the shading normal of `material` by the gradient of a height texture.
+pub struct BumpMap {
+    material: Rc<dyn Material>,
+    height: Rc<dyn Texture>,
+    strength: f64,
+}
+
+impl BumpMap {
+    pub fn new(material: Rc<dyn Material>, height: Rc<dyn Texture>, strength: f64) -> Self {
+        Self {
+            material,
+            height,
+            strength,
+        }
+    }
+
+    fn height_at(&self, u: f64, v: f64, p: &Point3) -> f64 {
+        self.strength * self.height.value(u, v, p).luminance()
+    }
+
+    fn shading_record<'a>(&self, rec: &HitRecord<'a>) -> HitRecord<'a> {
+        let mut shading = *rec;
+        if rec.tangent.near_zero() || rec.bitangent.near_zero() {
+            return shading;
+        }
+
+        let h = self.height_at(rec.u, rec.v, &rec.p);
+        let h_u = self.height_at(rec.u + DELTA, rec.v, &(rec.p + DELTA * rec.tangent));
+        let h_v = self.height_at(rec.u, rec.v + DELTA, &(rec.p + DELTA * rec.bitangent));
+        let dh_du = (h_u - h) / DELTA;
+        let dh_dv = (h_v - h) / DELTA;
+
+        let n = rec.outward_normal();
+        let dpdu = rec.tangent + dh_du * n;
+        let dpdv = rec.bitangent + dh_dv * n;
+        let mut perturbed = dpdu.cross(&dpdv).unit_vector();
+        if perturbed.dot(&n) < 0.0 {
+            perturbed = -perturbed;
+        }
+
+        shading.normal = if rec.front_face {
+            perturbed
+        } else {
+            -perturbed
+        };
+        shading.tangent = dpdu;
+        shading.bitangent = dpdv;
+        shading
+    }
+}
+
+impl Material for BumpMap {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+        sampler: &mut SampleStream,
+    ) -> bool {
+        self.material.scatter(
+            r_in,
+            &self.shading_record(rec),
+            attenuation,
+            scattered,
+            sampler,
+        )
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.material.emitted(u, v, p)
+    }
+
+    fn medium(&self) -> Option<Medium> {
+        self.material.medium()
+    }
+}
@@ -1,20 +1,50 @@
 use crate::color::Color;
+use crate::error::{Error, Result};
 use crate::hit_record::HitRecord;
 use crate::material::Material;
+use crate::medium::{Ior, Medium};
 use crate::ray::Ray;
-
-use rand::random;
+use crate::sampler::SampleStream;
 
 pub struct Dielectric {
-    index_of_refraction: f64,
+    index_of_refraction: Ior,
+    medium: Medium,
 }
 
 impl Dielectric {
-    pub(crate) fn new(index_of_refraction: f64) -> Dielectric {
+    pub fn new(index_of_refraction: f64) -> Result<Dielectric> {
+        Error::check_positive("index of refraction", index_of_refraction)?;
+        Ok(Self::from_ior(Ior::Constant(index_of_refraction)))
+    }
+
+    pub fn from_ior(index_of_refraction: Ior) -> Dielectric {
         Self {
             index_of_refraction,
+            medium: Medium::new(index_of_refraction, Color::default(), 0),
         }
     }
+
+    /// n(λ) = a + b / λ², with λ in micrometres.
+    pub fn cauchy(a: f64, b: f64) -> Dielectric {
+        Self::from_ior(Ior::Cauchy { a, b })
+    }
+
+    /// n²(λ) = 1 + Σ bᵢλ² / (λ² - cᵢ), with λ in micrometres.
+    pub fn sellmeier(b: [f64; 3], c: [f64; 3]) -> Dielectric {
+        Self::from_ior(Ior::Sellmeier { b, c })
+    }
+
+    /// Absorption coefficient per unit distance travelled inside the material.
+    pub fn absorption(mut self, absorption: Color) -> Self {
+        self.medium = Medium::new(self.index_of_refraction, absorption, self.medium.priority());
+        self
+    }
+
+    /// Where media overlap, the one with the highest priority is used.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.medium = Medium::new(self.index_of_refraction, self.medium.absorption(), priority);
+        self
+    }
 }
 
 fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
@@ -30,13 +60,15 @@ impl Material for Dielectric {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut SampleStream,
     ) -> bool {
         *attenuation = Color::new(1.0, 1.0, 1.0);
 
+        let index_of_refraction = self.index_of_refraction.at(r_in.wavelength());
         let refraction_ratio = if rec.front_face {
-            1.0 / self.index_of_refraction
+            rec.outside_ior / index_of_refraction
         } else {
-            self.index_of_refraction
+            index_of_refraction / rec.outside_ior
         };
 
         let unit_direction = r_in.direction().unit_vector();
@@ -45,12 +77,17 @@ impl Material for Dielectric {
 
         let cannot_refract = refraction_ratio * sin_theta > 1.0;
 
-        let direction = if cannot_refract || reflectance(cos_theta, refraction_ratio) > random() {
-            unit_direction.reflected(&rec.normal)
-        } else {
-            unit_direction.refracted(&rec.normal, refraction_ratio)
-        };
+        let direction =
+            if cannot_refract || reflectance(cos_theta, refraction_ratio) > sampler.get_1d() {
+                unit_direction.reflected(&rec.normal)
+            } else {
+                unit_direction.refracted(&rec.normal, refraction_ratio)
+            };
         *scattered = Ray::new(rec.p, direction, r_in.time());
         true
     }
+
+    fn medium(&self) -> Option<Medium> {
+        Some(self.medium)
+    }
 }
@@ -3,6 +3,7 @@ use crate::hit_record::HitRecord;
 use crate::material::Material;
 use crate::point3::Point3;
 use crate::ray::Ray;
+use crate::sampler::SampleStream;
 use crate::texture::{SolidColor, Texture};
 use std::rc::Rc;
 
@@ -11,7 +12,7 @@ pub struct DiffuseLight {
 }
 
 impl DiffuseLight {
-    pub(crate) fn new(tex: Rc<dyn Texture>) -> Self {
+    pub fn new(tex: Rc<dyn Texture>) -> Self {
         Self { tex }
     }
 
@@ -27,6 +28,7 @@ impl Material for DiffuseLight {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        _sampler: &mut SampleStream,
     ) -> bool {
         return false;
     }
@@ -2,6 +2,7 @@ use crate::color::Color;
 use crate::hit_record::HitRecord;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::sampler::SampleStream;
 use crate::texture::{SolidColor, Texture};
 use crate::vec3::Vec3;
 use std::rc::Rc;
@@ -14,7 +15,7 @@ impl Lambertian {
     pub fn new(albedo: Rc<dyn Texture>) -> Self {
         Self { albedo }
     }
-    pub(crate) fn new_with_color(albedo: Color) -> Lambertian {
+    pub fn new_with_color(albedo: Color) -> Lambertian {
         Self::new(Rc::new(SolidColor::new(albedo)))
     }
 }
@@ -26,8 +27,9 @@ impl Material for Lambertian {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut SampleStream,
     ) -> bool {
-        let mut scatter_direction = rec.normal + Vec3::random_unit_vector();
+        let mut scatter_direction = rec.normal + Vec3::sample_unit_vector(sampler.get_2d());
         if scatter_direction.near_zero() {
             scatter_direction = rec.normal;
         }
@@ -1,7 +1,9 @@
 use crate::color::Color;
+use crate::error::{Error, Result};
 use crate::hit_record::HitRecord;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::sampler::SampleStream;
 use crate::vec3::Vec3;
 
 pub struct Metal {
@@ -10,9 +12,15 @@ pub struct Metal {
 }
 
 impl Metal {
-    pub(crate) fn new(albedo: Color, fuzz: f64) -> Metal {
-        assert!(fuzz <= 1.0);
-        Self { albedo, fuzz }
+    /// Fails unless `fuzz` is between 0 and 1.
+    pub fn new(albedo: Color, fuzz: f64) -> Result<Metal> {
+        if !(0.0..=1.0).contains(&fuzz) {
+            return Err(Error::invalid(format!(
+                "metal fuzz must be between 0 and 1, got {}",
+                fuzz
+            )));
+        }
+        Ok(Self { albedo, fuzz })
     }
 }
 
@@ -23,11 +31,12 @@ impl Material for Metal {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut SampleStream,
     ) -> bool {
         let reflected = r_in.direction().unit_vector().reflected(&rec.normal);
         *scattered = Ray::new(
             rec.p,
-            reflected + self.fuzz * Vec3::random_unit_vector(),
+            reflected + self.fuzz * Vec3::sample_unit_vector(sampler.get_2d()),
             r_in.time(),
         );
         *attenuation = self.albedo;
@@ -1,17 +1,23 @@
+mod bump_map;
 mod dielectric;
 mod diffuse_light;
 mod lambertian;
 mod metal;
+mod normal_map;
 
+pub use bump_map::BumpMap;
 pub use dielectric::Dielectric;
 pub use diffuse_light::DiffuseLight;
 pub use lambertian::Lambertian;
 pub use metal::Metal;
+pub use normal_map::NormalMap;
 
 use crate::color::Color;
 use crate::hit_record::HitRecord;
+use crate::medium::Medium;
 use crate::point3::Point3;
 use crate::ray::Ray;
+use crate::sampler::SampleStream;
 
 pub trait Material {
     fn scatter(
@@ -20,9 +26,14 @@ pub trait Material {
         rec: &HitRecord,
         attenuation: &mut Color,
         scattered: &mut Ray,
+        sampler: &mut SampleStream,
     ) -> bool;
 
     fn emitted(&self, u: f64, v: f64, p: &Point3) -> Color {
         Color::new(0.0, 0.0, 0.0)
     }
+
+    fn medium(&self) -> Option<Medium> {
+        None
+    }
 }
@@ -0,0 +1,82 @@
+use crate::color::Color;
+use crate::hit_record::HitRecord;
+use crate::material::Material;
+use crate::medium::Medium;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::texture::Texture;
+use crate::vec3::Vec3;
+use std::rc::Rc;
+
+/// Replaces the shading normal of `material` with one read from a tangent-space
+/// normal map, where the texture's red, green and blue channels encode the
+/// tangent, bitangent and normal components.
+pub struct NormalMap {
+    material: Rc<dyn Material>,
+    map: Rc<dyn Texture>,
+    strength: f64,
+}
+
+impl NormalMap {
+    pub fn new(material: Rc<dyn Material>, map: Rc<dyn Texture>, strength: f64) -> Self {
+        Self {
+            material,
+            map,
+            strength,
+        }
+    }
+
+    fn shading_record<'a>(&self, rec: &HitRecord<'a>) -> HitRecord<'a> {
+        let mut shading = *rec;
+        if rec.tangent.near_zero() {
+            return shading;
+        }
+
+        let n = rec.outward_normal();
+        let t = (rec.tangent - rec.tangent.dot(&n) * n).unit_vector();
+        let b = n.cross(&t);
+
+        let c = self.map.value(rec.u, rec.v, &rec.p);
+        let local = Vec3::new(
+            self.strength * (2.0 * c.r() - 1.0),
+            self.strength * (2.0 * c.g() - 1.0),
+            2.0 * c.b() - 1.0,
+        );
+        let perturbed = (local.x() * t + local.y() * b + local.z() * n).unit_vector();
+
+        shading.normal = if rec.front_face {
+            perturbed
+        } else {
+            -perturbed
+        };
+        shading
+    }
+}
+
+impl Material for NormalMap {
+    fn scatter(
+        &self,
+        r_in: &Ray,
+        rec: &HitRecord,
+        attenuation: &mut Color,
+        scattered: &mut Ray,
+        sampler: &mut SampleStream,
+    ) -> bool {
+        self.material.scatter(
+            r_in,
+            &self.shading_record(rec),
+            attenuation,
+            scattered,
+            sampler,
+        )
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: &Point3) -> Color {
+        self.material.emitted(u, v, p)
+    }
+
+    fn medium(&self) -> Option<Medium> {
+        self.material.medium()
+    }
+}
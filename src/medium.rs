@@ -0,0 +1,158 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::color::Color;
+use crate::spectrum;
+
+static NEXT_MEDIUM_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Wavelength used for dispersive media when rendering in RGB (the helium d-line).
+const REFERENCE_WAVELENGTH: f64 = 587.6;
+
+/// A possibly wavelength dependent index of refraction. Dispersion formulas take
+/// the wavelength in micrometres, as they are usually tabulated.
+#[derive(Copy, Clone, Debug)]
+pub enum Ior {
+    Constant(f64),
+    Cauchy { a: f64, b: f64 },
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Ior {
+    pub const BK7: Ior = Ior::Sellmeier {
+        b: [1.03961212, 0.231792344, 1.01046945],
+        c: [0.00600069867, 0.0200179144, 103.560653],
+    };
+    pub const FUSED_SILICA: Ior = Ior::Sellmeier {
+        b: [0.6961663, 0.4079426, 0.8974794],
+        c: [0.00467914826, 0.0135120631, 97.9340025],
+    };
+    pub const DIAMOND: Ior = Ior::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030625, 0.011236, 0.0],
+    };
+
+    pub fn at(&self, wavelength: Option<f64>) -> f64 {
+        let micrometres = wavelength.unwrap_or(REFERENCE_WAVELENGTH) / 1000.0;
+        let l2 = micrometres * micrometres;
+        match *self {
+            Ior::Constant(n) => n,
+            Ior::Cauchy { a, b } => a + b / l2,
+            Ior::Sellmeier { b, c } => {
+                (1.0 + (0..3).map(|i| b[i] * l2 / (l2 - c[i])).sum::<f64>()).sqrt()
+            }
+        }
+    }
+}
+
+/// The participating medium enclosed by a dielectric surface.
+///
+/// Media with a higher `priority` win where objects overlap, so a liquid can be
+/// modelled slightly larger than the cavity of the glass holding it.
+#[derive(Copy, Clone)]
+pub struct Medium {
+    id: usize,
+    ior: Ior,
+    absorption: Color,
+    priority: i32,
+}
+
+impl Medium {
+    pub fn new(ior: Ior, absorption: Color, priority: i32) -> Self {
+        Self {
+            id: NEXT_MEDIUM_ID.fetch_add(1, Ordering::Relaxed),
+            ior,
+            absorption,
+            priority,
+        }
+    }
+
+    pub fn absorption(&self) -> Color {
+        self.absorption
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Beer-Lambert transmittance after travelling `distance` through the medium.
+    pub fn transmittance(&self, distance: f64, wavelength: Option<f64>) -> Color {
+        let absorption = spectrum::project(self.absorption, wavelength);
+        let channel = |sigma: f64| {
+            if sigma <= 0.0 {
+                1.0
+            } else {
+                (-sigma * distance).exp()
+            }
+        };
+        Color::new(
+            channel(absorption.r()),
+            channel(absorption.g()),
+            channel(absorption.b()),
+        )
+    }
+}
+
+/// The media a ray is currently inside of, in the order they were entered.
+#[derive(Clone, Default)]
+pub struct MediumStack {
+    media: Vec<Medium>,
+}
+
+impl MediumStack {
+    /// The medium the ray is travelling through: the highest priority one,
+    /// preferring the most recently entered on ties.
+    pub fn current(&self) -> Option<&Medium> {
+        self.media
+            .iter()
+            .rev()
+            .reduce(|best, m| if m.priority > best.priority { m } else { best })
+    }
+
+    pub fn ior(&self, wavelength: Option<f64>) -> f64 {
+        self.current().map_or(1.0, |m| m.ior.at(wavelength))
+    }
+
+    pub fn transmittance(&self, distance: f64, wavelength: Option<f64>) -> Color {
+        self.current().map_or(Color::new(1.0, 1.0, 1.0), |m| {
+            m.transmittance(distance, wavelength)
+        })
+    }
+
+    /// A surface is a false hit if the ray is inside another medium which
+    /// takes priority over the one bounded by the surface.
+    pub fn is_false_hit(&self, medium: &Medium) -> bool {
+        self.media
+            .iter()
+            .any(|m| m.id != medium.id && m.priority > medium.priority)
+    }
+
+    /// The index of refraction on the other side of a surface of `medium`.
+    pub fn outside_ior(&self, medium: &Medium, entering: bool, wavelength: Option<f64>) -> f64 {
+        if entering {
+            self.ior(wavelength)
+        } else {
+            let mut rest = self.clone();
+            rest.exit(medium);
+            rest.ior(wavelength)
+        }
+    }
+
+    pub fn enter(&mut self, medium: Medium) {
+        self.media.push(medium);
+    }
+
+    pub fn exit(&mut self, medium: &Medium) {
+        if let Some(i) = self.media.iter().rposition(|m| m.id == medium.id) {
+            self.media.remove(i);
+        }
+    }
+
+    /// Updates the stack for a ray crossing a surface of `medium`.
+    pub fn cross(&mut self, medium: Medium, entering: bool) {
+        if entering {
+            self.enter(medium)
+        } else {
+            self.exit(&medium)
+        }
+    }
+}
@@ -1,10 +1,25 @@
+//! Procedural noise for textures. Every generator is built from a seed, so the
+//! same seed gives the same pattern on every run.
+
+use crate::error::{Error, Result};
+use crate::interval::Interval;
 use crate::point3::Point3;
 use crate::vec3::Vec3;
-use rand::{thread_rng, Rng};
-use std::ops::MulAssign;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::rc::Rc;
 
 const POINT_COUNT: usize = 256;
+
+/// A scalar field over space, varying on the scale of a unit cell.
+pub trait Noise {
+    fn noise(&self, p: &Point3) -> f64;
+
+    /// The values `noise` returns, or nearly all of them.
+    fn range(&self) -> Interval;
+}
+
+/// Gradient noise on a cubic lattice.
 pub struct Perlin {
     ranvec: Vec<Vec3>,
     perm_x: Vec<usize>,
@@ -13,20 +28,41 @@ pub struct Perlin {
 }
 
 impl Perlin {
-    pub fn new() -> Self {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
         let mut ranvec = Vec::with_capacity(POINT_COUNT);
         for _ in 0..POINT_COUNT {
-            ranvec.push(Vec3::random_range(-1.0, 1.0).unit_vector())
+            ranvec.push(random_vector(&mut rng, -1.0, 1.0).unit_vector())
         }
         Self {
             ranvec,
-            perm_x: perlin_generate_perm(),
-            perm_y: perlin_generate_perm(),
-            perm_z: perlin_generate_perm(),
+            perm_x: perlin_generate_perm(&mut rng),
+            perm_y: perlin_generate_perm(&mut rng),
+            perm_z: perlin_generate_perm(&mut rng),
+        }
+    }
+
+    pub fn turb(&self, p: &Point3) -> f64 {
+        self.turb_depth(p, 7)
+    }
+
+    pub fn turb_depth(&self, p: &Point3, depth: i32) -> f64 {
+        let mut acc = 0.0;
+        let mut weight = 1.0;
+        let mut p = p.clone();
+
+        for _ in 0..depth {
+            acc += weight * self.noise(&p);
+            weight *= 0.5;
+            p *= 2.0;
         }
+
+        acc.abs()
     }
+}
 
-    pub fn noise(&self, p: &Point3) -> f64 {
+impl Noise for Perlin {
+    fn noise(&self, p: &Point3) -> f64 {
         let u = p.x() - p.x().floor();
         let v = p.y() - p.y().floor();
         let w = p.z() - p.z().floor();
@@ -55,38 +91,283 @@ impl Perlin {
         perlin_interpolation(c, u, v, w)
     }
 
-    pub fn turb(&self, p: &Point3) -> f64 {
-        self.turb_depth(p, 7)
+    fn range(&self) -> Interval {
+        Interval::new(-1.0, 1.0)
     }
+}
+
+/// Gradient noise on a simplex lattice, which has fewer directional artefacts
+/// than Perlin noise and is cheaper to evaluate.
+pub struct Simplex {
+    perm: Vec<usize>,
+}
+
+impl Simplex {
+    const SKEW: f64 = 1.0 / 3.0;
+    const UNSKEW: f64 = 1.0 / 6.0;
+
+    /// The edge midpoints of a cube.
+    const GRADIENTS: [[f64; 3]; 12] = [
+        [1.0, 1.0, 0.0],
+        [-1.0, 1.0, 0.0],
+        [1.0, -1.0, 0.0],
+        [-1.0, -1.0, 0.0],
+        [1.0, 0.0, 1.0],
+        [-1.0, 0.0, 1.0],
+        [1.0, 0.0, -1.0],
+        [-1.0, 0.0, -1.0],
+        [0.0, 1.0, 1.0],
+        [0.0, -1.0, 1.0],
+        [0.0, 1.0, -1.0],
+        [0.0, -1.0, -1.0],
+    ];
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            perm: perlin_generate_perm(&mut StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    fn hash(&self, i: i64, j: i64, k: i64) -> usize {
+        let k = self.perm[(k & 255) as usize];
+        let j = self.perm[((j + k as i64) & 255) as usize];
+        self.perm[((i + j as i64) & 255) as usize]
+    }
+}
+
+impl Noise for Simplex {
+    fn noise(&self, p: &Point3) -> f64 {
+        // The cell of the skewed lattice, and the corner of its simplex nearest p.
+        let s = (p.x() + p.y() + p.z()) * Self::SKEW;
+        let (i, j, k) = (
+            (p.x() + s).floor(),
+            (p.y() + s).floor(),
+            (p.z() + s).floor(),
+        );
+        let t = (i + j + k) * Self::UNSKEW;
+        let d0 = [p.x() - i + t, p.y() - j + t, p.z() - k + t];
+
+        // The simplex is picked by the order of the offset's components, and
+        // is walked one axis at a time from the first corner to the last.
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| d0[b].total_cmp(&d0[a]));
+        let mut corner = [0i64; 3];
 
-    pub fn turb_depth(&self, p: &Point3, depth: i32) -> f64 {
         let mut acc = 0.0;
-        let mut weight = 1.0;
-        let mut p = p.clone();
+        for step in 0..4 {
+            if step > 0 {
+                corner[order[step - 1]] += 1;
+            }
+            let offset = step as f64 * Self::UNSKEW;
+            let d = [
+                d0[0] - corner[0] as f64 + offset,
+                d0[1] - corner[1] as f64 + offset,
+                d0[2] - corner[2] as f64 + offset,
+            ];
+            let falloff = 0.6 - d[0] * d[0] - d[1] * d[1] - d[2] * d[2];
+            if falloff > 0.0 {
+                let hash = self.hash(
+                    i as i64 + corner[0],
+                    j as i64 + corner[1],
+                    k as i64 + corner[2],
+                );
+                let g = Self::GRADIENTS[hash % 12];
+                let falloff = falloff * falloff;
+                acc += falloff * falloff * (g[0] * d[0] + g[1] * d[1] + g[2] * d[2]);
+            }
+        }
 
-        for _ in 0..depth {
-            acc += weight * self.noise(&p);
-            weight *= 0.5;
-            p *= 2.0;
+        // Scaled to about [-1, 1].
+        32.0 * acc
+    }
+
+    fn range(&self) -> Interval {
+        Interval::new(-1.0, 1.0)
+    }
+}
+
+/// Which distances to the nearest feature points [`Worley`] noise returns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorleyFeature {
+    /// To the nearest point, giving rounded cells.
+    F1,
+    /// To the second nearest point.
+    F2,
+    /// The difference of the two, which is zero along cell borders.
+    F2MinusF1,
+}
+
+/// Cellular noise from the distances to feature points scattered one per
+/// lattice cell.
+pub struct Worley {
+    feature: WorleyFeature,
+    points: Vec<Vec3>,
+    perm: Vec<usize>,
+}
+
+impl Worley {
+    pub fn new(seed: u64, feature: WorleyFeature) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut points = Vec::with_capacity(POINT_COUNT);
+        for _ in 0..POINT_COUNT {
+            points.push(random_vector(&mut rng, 0.0, 1.0));
         }
+        Self {
+            feature,
+            points,
+            perm: perlin_generate_perm(&mut rng),
+        }
+    }
 
-        acc.abs()
+    /// The feature point in cell `(i, j, k)`.
+    fn point(&self, i: i64, j: i64, k: i64) -> Point3 {
+        let k_hash = self.perm[(k & 255) as usize];
+        let j_hash = self.perm[((j + k_hash as i64) & 255) as usize];
+        let hash = self.perm[((i + j_hash as i64) & 255) as usize];
+        Point3::new(i as f64, j as f64, k as f64) + self.points[hash]
+    }
+}
+
+impl Noise for Worley {
+    fn noise(&self, p: &Point3) -> f64 {
+        let (i, j, k) = (
+            p.x().floor() as i64,
+            p.y().floor() as i64,
+            p.z().floor() as i64,
+        );
+
+        // Both nearest points lie in the cells around p's own.
+        let mut f1 = f64::INFINITY;
+        let mut f2 = f64::INFINITY;
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                for dk in -1..=1 {
+                    let distance = (self.point(i + di, j + dj, k + dk) - *p).length();
+                    if distance < f1 {
+                        f2 = f1;
+                        f1 = distance;
+                    } else if distance < f2 {
+                        f2 = distance;
+                    }
+                }
+            }
+        }
+
+        match self.feature {
+            WorleyFeature::F1 => f1,
+            WorleyFeature::F2 => f2,
+            WorleyFeature::F2MinusF1 => f2 - f1,
+        }
+    }
+
+    fn range(&self) -> Interval {
+        match self.feature {
+            WorleyFeature::F1 | WorleyFeature::F2MinusF1 => Interval::new(0.0, 1.0),
+            WorleyFeature::F2 => Interval::new(0.0, 1.5),
+        }
+    }
+}
+
+/// How the octaves of a [`Fractal`] are combined.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FractalKind {
+    /// Fractional Brownian motion, the plain sum of the octaves.
+    Fbm,
+    /// Sharp ridges where the basis crosses the middle of its range, as in
+    /// mountain ranges.
+    Ridged,
+    /// Rounded bumps with creases between them, as in clouds.
+    Billow,
+}
+
+/// Octaves of a basis noise summed at rising frequencies and falling
+/// amplitudes. Each octave is `lacunarity` times the frequency and `gain` times
+/// the amplitude of the one before.
+pub struct Fractal {
+    basis: Rc<dyn Noise>,
+    kind: FractalKind,
+    octaves: u32,
+    lacunarity: f64,
+    gain: f64,
+}
+
+impl Fractal {
+    pub fn new(
+        basis: Rc<dyn Noise>,
+        kind: FractalKind,
+        octaves: u32,
+        lacunarity: f64,
+        gain: f64,
+    ) -> Result<Self> {
+        if octaves < 1 {
+            return Err(Error::invalid("a fractal needs at least one octave"));
+        }
+        Error::check_positive("lacunarity", lacunarity)?;
+        Error::check_positive("gain", gain)?;
+        Ok(Self {
+            basis,
+            kind,
+            octaves,
+            lacunarity,
+            gain,
+        })
     }
 }
 
-fn perlin_generate_perm() -> Vec<usize> {
+impl Noise for Fractal {
+    fn noise(&self, p: &Point3) -> f64 {
+        let range = self.basis.range();
+        let mut p = *p;
+        let mut amplitude = 1.0;
+        let mut acc = 0.0;
+        let mut total = 0.0;
+
+        for _ in 0..self.octaves {
+            // The basis, stretched over [-1, 1].
+            let n = 2.0 * (self.basis.noise(&p) - range.min) / range.size() - 1.0;
+            acc += amplitude
+                * match self.kind {
+                    FractalKind::Fbm => n,
+                    FractalKind::Ridged => (1.0 - n.abs()) * (1.0 - n.abs()),
+                    FractalKind::Billow => 2.0 * n.abs() - 1.0,
+                };
+            total += amplitude;
+            amplitude *= self.gain;
+            p *= self.lacunarity;
+        }
+
+        acc / total
+    }
+
+    fn range(&self) -> Interval {
+        match self.kind {
+            FractalKind::Ridged => Interval::new(0.0, 1.0),
+            FractalKind::Fbm | FractalKind::Billow => Interval::new(-1.0, 1.0),
+        }
+    }
+}
+
+/// Each component uniform in `[min, max)`.
+fn random_vector(rng: &mut StdRng, min: f64, max: f64) -> Vec3 {
+    Vec3::new(
+        rng.gen_range(min..max),
+        rng.gen_range(min..max),
+        rng.gen_range(min..max),
+    )
+}
+
+fn perlin_generate_perm(rng: &mut StdRng) -> Vec<usize> {
     let mut p = Vec::new();
     for i in 0..POINT_COUNT {
         p.push(i)
     }
 
-    permute(p, POINT_COUNT)
+    permute(p, POINT_COUNT, rng)
 }
 
-fn permute(mut p: Vec<usize>, n: usize) -> Vec<usize> {
-    let mut rng = thread_rng();
-    for i in (n - 1)..0 {
-        let target = rng.gen();
+fn permute(mut p: Vec<usize>, n: usize, rng: &mut StdRng) -> Vec<usize> {
+    for i in (1..n).rev() {
+        let target = rng.gen_range(0..=i);
         p.swap(i, target);
     }
     p
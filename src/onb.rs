@@ -0,0 +1,49 @@
+use crate::vec3::Vec3;
+
+/// A right-handed orthonormal basis, so that `u × v = w`.
+#[derive(Copy, Clone, Debug)]
+pub struct Onb {
+    axis: [Vec3; 3],
+}
+
+impl Onb {
+    pub fn build_from_w(w: &Vec3) -> Self {
+        let unit_w = w.unit_vector();
+        let a = if unit_w.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = unit_w.cross(&a).unit_vector();
+        let u = v.cross(&unit_w);
+        Self {
+            axis: [u, v, unit_w],
+        }
+    }
+
+    pub fn u(&self) -> Vec3 {
+        self.axis[0]
+    }
+
+    pub fn v(&self) -> Vec3 {
+        self.axis[1]
+    }
+
+    pub fn w(&self) -> Vec3 {
+        self.axis[2]
+    }
+
+    /// Converts local coordinates to world space.
+    pub fn local(&self, a: f64, b: f64, c: f64) -> Vec3 {
+        a * self.u() + b * self.v() + c * self.w()
+    }
+
+    pub fn local_vec(&self, a: &Vec3) -> Vec3 {
+        self.local(a.x(), a.y(), a.z())
+    }
+
+    /// Converts a world space vector into local coordinates.
+    pub fn world_to_local(&self, a: &Vec3) -> Vec3 {
+        Vec3::new(a.dot(&self.u()), a.dot(&self.v()), a.dot(&self.w()))
+    }
+}
@@ -1,139 +1,79 @@
-use std::ops;
-use std::ops::{Mul, MulAssign};
-
+use crate::simd::{lanes_op, F3};
 use crate::vec3::Vec3;
 
-#[derive(Copy, Clone, Debug)]
-pub struct Point3 {
-    x: f64,
-    y: f64,
-    z: f64,
-}
-
-impl Default for Point3 {
-    fn default() -> Self {
-        Self {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        }
-    }
-}
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Point3(F3);
 
 impl Point3 {
-    pub(crate) fn new(x: f64, y: f64, z: f64) -> Self {
-        Self { x, y, z }
-    }
-    pub fn x(&self) -> f64 {
-        self.x
-    }
-    pub fn y(&self) -> f64 {
-        self.y
-    }
-    pub fn z(&self) -> f64 {
-        self.z
+    #[inline]
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self(F3::new(x, y, z))
     }
-}
 
-impl Point3 {
-    pub fn dot(&self, other: &Vec3) -> f64 {
-        self.x * other.x() + self.y * other.y() + self.z * other.z()
+    #[inline]
+    pub(crate) fn from_lanes(lanes: F3) -> Self {
+        Self(lanes)
     }
-}
-
-impl ops::Add<Vec3> for &Point3 {
-    type Output = Point3;
 
-    fn add(self, rhs: Vec3) -> Self::Output {
-        Self::Output {
-            x: self.x + rhs.x(),
-            y: self.y + rhs.y(),
-            z: self.z + rhs.z(),
-        }
+    #[inline]
+    pub(crate) fn lanes(&self) -> F3 {
+        self.0
     }
-}
-
-impl ops::Sub<Vec3> for Point3 {
-    type Output = Point3;
 
-    fn sub(self, rhs: Vec3) -> Self::Output {
-        Self::Output {
-            x: self.x - rhs.x(),
-            y: self.y - rhs.y(),
-            z: self.z - rhs.z(),
-        }
+    #[inline]
+    pub fn x(&self) -> f64 {
+        self.0.x()
     }
-}
-
-impl ops::Add<&Vec3> for Point3 {
-    type Output = Point3;
-
-    fn add(self, rhs: &Vec3) -> Self::Output {
-        Point3 {
-            x: self.x + rhs.x(),
-            y: self.y + rhs.y(),
-            z: self.z + rhs.z(),
-        }
+    #[inline]
+    pub fn y(&self) -> f64 {
+        self.0.y()
     }
-}
-
-impl ops::Sub<&Point3> for &Point3 {
-    type Output = Vec3;
-
-    fn sub(self, rhs: &Point3) -> Self::Output {
-        Self::Output::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    #[inline]
+    pub fn z(&self) -> f64 {
+        self.0.z()
     }
-}
-
-impl ops::Sub<Point3> for &Point3 {
-    type Output = Vec3;
 
-    fn sub(self, rhs: Point3) -> Self::Output {
-        Self::Output::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    /// The coordinate along axis `n`, counting x as 0.
+    ///
+    /// # Panics
+    /// If `n` is not 0, 1 or 2.
+    #[inline]
+    pub fn axis(&self, n: i32) -> f64 {
+        assert!((0..3).contains(&n), "Invalid axis {} on Point3", n);
+        self.0.get(n as usize)
     }
 }
 
-impl ops::Sub<Point3> for Point3 {
-    type Output = Vec3;
-
-    fn sub(self, rhs: Point3) -> Self::Output {
-        &self - rhs
+impl Point3 {
+    #[inline]
+    pub fn dot(&self, other: &Vec3) -> f64 {
+        self.0.dot(other.lanes())
     }
 }
 
-impl ops::Index<i32> for Point3 {
-    type Output = f64;
-
-    fn index(&self, index: i32) -> &Self::Output {
-        match index {
-            0 => &self.x,
-            1 => &self.y,
-            2 => &self.z,
-            _ => {
-                panic!("Invalid Index on Point3")
-            }
-        }
+lanes_op!(Add, add, Point3, Vec3, Point3, |a, b| Point3(
+    a.0 + b.lanes()
+));
+lanes_op!(Sub, sub, Point3, Vec3, Point3, |a, b| Point3(
+    a.0 - b.lanes()
+));
+lanes_op!(Sub, sub, Point3, Point3, Vec3, |a, b| Vec3::from_lanes(
+    a.0 - b.0
+));
+lanes_op!(Mul, mul, f64, Point3, Point3, |a, b| Point3(
+    F3::scalar(a) * b.0
+));
+
+impl std::ops::MulAssign<f64> for Point3 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: f64) {
+        self.0 = self.0 * F3::scalar(rhs);
     }
 }
 
 impl From<Point3> for Vec3 {
+    #[inline]
     fn from(val: Point3) -> Self {
-        Vec3::new(val.x, val.y, val.z)
-    }
-}
-
-impl Mul<&Point3> for f64 {
-    type Output = Point3;
-
-    fn mul(self, rhs: &Point3) -> Self::Output {
-        Point3::new(self * rhs.x, self * rhs.y, self * rhs.z)
-    }
-}
-
-impl MulAssign<f64> for Point3 {
-    fn mul_assign(&mut self, rhs: f64) {
-        self.x *= rhs;
-        self.y *= rhs;
-        self.z *= rhs;
+        Vec3::from_lanes(val.0)
     }
 }
@@ -0,0 +1,170 @@
+// Closed form polynomial root finding after Jochen Schwarze, "Cubic and Quartic
+// Roots", Graphics Gems (1990). Coefficients are given lowest degree first.
+
+use std::ops::{Deref, DerefMut};
+
+const EPSILON: f64 = 1e-9;
+
+/// Up to four roots, or hits found from them, kept inline so that finding them
+/// for every ray allocates nothing.
+#[derive(Clone, Copy, Default)]
+pub struct Roots<T = f64> {
+    items: [T; 4],
+    len: usize,
+}
+
+impl<T> Roots<T> {
+    pub fn push(&mut self, item: T) {
+        self.items[self.len] = item;
+        self.len += 1;
+    }
+}
+
+impl<T> Deref for Roots<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items[..self.len]
+    }
+}
+
+impl<T> DerefMut for Roots<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.items[..self.len]
+    }
+}
+
+impl<T: Copy + Default> FromIterator<T> for Roots<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut roots = Self::default();
+        for item in iter {
+            roots.push(item);
+        }
+        roots
+    }
+}
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < EPSILON
+}
+
+/// Real roots of c[2]x² + c[1]x + c[0].
+pub fn solve_quadratic(c: [f64; 3]) -> Roots {
+    let p = c[1] / (2.0 * c[2]);
+    let q = c[0] / c[2];
+    let d = p * p - q;
+
+    let mut roots = Roots::default();
+    if is_zero(d) {
+        roots.push(-p);
+    } else if d > 0.0 {
+        let sqrt_d = d.sqrt();
+        roots.push(sqrt_d - p);
+        roots.push(-sqrt_d - p);
+    }
+    roots
+}
+
+/// Real roots of c[3]x³ + c[2]x² + c[1]x + c[0].
+pub fn solve_cubic(c: [f64; 4]) -> Roots {
+    let a = c[2] / c[3];
+    let b = c[1] / c[3];
+    let c = c[0] / c[3];
+
+    // Substitute x = y - a/3 to eliminate the quadratic term: y³ + py + q = 0.
+    let sq_a = a * a;
+    let p = (-sq_a / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * sq_a - a * b / 3.0 + c) / 2.0;
+
+    let cb_p = p * p * p;
+    let d = q * q + cb_p;
+
+    let mut roots = Roots::default();
+    if is_zero(d) {
+        if is_zero(q) {
+            roots.push(0.0);
+        } else {
+            let u = (-q).cbrt();
+            roots.push(2.0 * u);
+            roots.push(-u);
+        }
+    } else if d < 0.0 {
+        let phi = (-q / (-cb_p).sqrt()).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        roots.push(t * phi.cos());
+        roots.push(-t * (phi + std::f64::consts::FRAC_PI_3).cos());
+        roots.push(-t * (phi - std::f64::consts::FRAC_PI_3).cos());
+    } else {
+        let sqrt_d = d.sqrt();
+        roots.push((sqrt_d - q).cbrt() - (sqrt_d + q).cbrt());
+    }
+
+    for y in roots.iter_mut() {
+        *y -= a / 3.0;
+    }
+    roots
+}
+
+/// Real roots of c[4]x⁴ + c[3]x³ + c[2]x² + c[1]x + c[0], polished with a few
+/// Newton iterations.
+pub fn solve_quartic(c: [f64; 5]) -> Roots {
+    let a = c[3] / c[4];
+    let b = c[2] / c[4];
+    let cc = c[1] / c[4];
+    let d = c[0] / c[4];
+
+    // Substitute x = y - a/4 to eliminate the cubic term: y⁴ + py² + qy + r = 0.
+    let sq_a = a * a;
+    let p = -3.0 / 8.0 * sq_a + b;
+    let q = sq_a * a / 8.0 - a * b / 2.0 + cc;
+    let r = -3.0 / 256.0 * sq_a * sq_a + sq_a * b / 16.0 - a * cc / 4.0 + d;
+
+    let mut roots = if is_zero(r) {
+        let mut roots = solve_cubic([q, p, 0.0, 1.0]);
+        roots.push(0.0);
+        roots
+    } else {
+        // Take one real root of the resolvent cubic to split into two quadratics.
+        let z = solve_cubic([r * p / 2.0 - q * q / 8.0, -r, -p / 2.0, 1.0])[0];
+
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if is_zero(u) {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return Roots::default();
+        };
+        let v = if is_zero(v) {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return Roots::default();
+        };
+
+        let mut roots = solve_quadratic([z - u, if q < 0.0 { -v } else { v }, 1.0]);
+        for y in solve_quadratic([z + u, if q < 0.0 { v } else { -v }, 1.0]).iter() {
+            roots.push(*y);
+        }
+        roots
+    };
+
+    for y in roots.iter_mut() {
+        *y = polish(&c, *y - a / 4.0);
+    }
+    roots
+}
+
+fn polish(c: &[f64; 5], mut x: f64) -> f64 {
+    for _ in 0..2 {
+        let f = (((c[4] * x + c[3]) * x + c[2]) * x + c[1]) * x + c[0];
+        let df = ((4.0 * c[4] * x + 3.0 * c[3]) * x + 2.0 * c[2]) * x + c[1];
+        if df.abs() < EPSILON {
+            break;
+        }
+        x -= f / df;
+    }
+    x
+}
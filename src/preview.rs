@@ -0,0 +1,260 @@
+use std::io::{self, BufRead, BufReader, Cursor, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use image::{ImageFormat, Rgb, RgbImage};
+
+use crate::color::Color;
+use crate::error::{Error, Result};
+
+/// How often the server checks for new connections and whether to stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a finished render is still served for a page which is watching,
+/// so that it can pick up the final image. Longer than the page's refresh.
+const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<title>Render preview</title>
+<style>
+body { background: #202020; color: #e0e0e0; font-family: sans-serif; }
+img { image-rendering: pixelated; max-width: 100%; }
+</style>
+</head>
+<body>
+<img id="image" src="/image.png">
+<p id="stats"></p>
+<script>
+async function refresh() {
+    const stats = await (await fetch("/stats.json")).json();
+    document.getElementById("stats").textContent =
+        `${stats.rows_done}/${stats.height} rows (${(100 * stats.progress).toFixed(2)}%), ` +
+        `${stats.elapsed.toFixed(1)}s spent, ${stats.remaining.toFixed(1)}s remaining` +
+        (stats.finished ? ", finished" : "");
+    document.getElementById("image").src = "/image.png?" + Date.now();
+    if (!stats.finished) {
+        setTimeout(refresh, 2000);
+    }
+}
+refresh();
+</script>
+</body>
+</html>
+"#;
+
+/// What has been rendered so far.
+struct Progress {
+    image: RgbImage,
+    samples_per_pixel: i32,
+    rows_done: u32,
+    start: Instant,
+    finished: bool,
+    /// Whether any page has asked for the render.
+    watched: bool,
+    /// Whether the page has been told the render finished, and has then
+    /// fetched the final image.
+    told_finished: bool,
+    delivered: bool,
+}
+
+impl Progress {
+    fn stats(&self) -> String {
+        let height = self.image.height();
+        let progress = self.rows_done as f64 / height.max(1) as f64;
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let remaining = if self.rows_done == 0 {
+            0.0
+        } else {
+            elapsed / self.rows_done as f64 * (height - self.rows_done) as f64
+        };
+        format!(
+            "{{\"width\":{},\"height\":{},\"samples_per_pixel\":{},\"rows_done\":{},\"progress\":{},\"elapsed\":{},\"remaining\":{},\"finished\":{}}}",
+            self.image.width(),
+            height,
+            self.samples_per_pixel,
+            self.rows_done,
+            progress,
+            elapsed,
+            remaining,
+            self.finished
+        )
+    }
+}
+
+/// Serves an in-progress render over HTTP: an auto-refreshing page at `/`,
+/// the image so far at `/image.png` and render statistics at `/stats.json`.
+/// The server stops when this is dropped, after giving a watching page the
+/// chance to show the finished image.
+///
+/// Anyone who can reach the server can watch, so it only listens on loopback
+/// addresses.
+pub struct PreviewServer {
+    address: SocketAddr,
+    progress: Arc<Mutex<Progress>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PreviewServer {
+    pub fn start(
+        address: impl ToSocketAddrs,
+        width: u32,
+        height: u32,
+        samples_per_pixel: i32,
+    ) -> Result<Self> {
+        let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+        if let Some(public) = addresses.iter().find(|a| !a.ip().is_loopback()) {
+            return Err(Error::invalid(format!(
+                "the preview is only served on loopback addresses, not {}",
+                public
+            )));
+        }
+        let listener = TcpListener::bind(&addresses[..])?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+
+        let progress = Arc::new(Mutex::new(Progress {
+            image: RgbImage::new(width, height),
+            samples_per_pixel,
+            rows_done: 0,
+            start: Instant::now(),
+            finished: false,
+            watched: false,
+            told_finished: false,
+            delivered: false,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let (progress, stop) = (progress.clone(), stop.clone());
+            thread::spawn(move || serve(listener, progress, stop))
+        };
+
+        Ok(Self {
+            address,
+            progress,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Where the server listens, with the port filled in when it was left to
+    /// the system.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Stores a finished pixel; `color` is already gamma corrected.
+    pub fn set_pixel(&self, x: u32, y: u32, color: Color) {
+        let pixel = Rgb([color.r_as_u8(), color.g_as_u8(), color.b_as_u8()]);
+        self.progress.lock().unwrap().image.put_pixel(x, y, pixel);
+    }
+
+    pub fn row_done(&self) {
+        self.progress.lock().unwrap().rows_done += 1;
+    }
+
+    pub fn finish(&self) {
+        self.progress.lock().unwrap().finished = true;
+    }
+}
+
+impl Drop for PreviewServer {
+    /// Once the render has finished, waits for a watching page to fetch the
+    /// final image, for at most [`GRACE_PERIOD`].
+    fn drop(&mut self) {
+        let deadline = Instant::now() + GRACE_PERIOD;
+        while Instant::now() < deadline {
+            let progress = self.progress.lock().unwrap();
+            if !progress.finished || !progress.watched || progress.delivered {
+                break;
+            }
+            drop(progress);
+            thread::sleep(POLL_INTERVAL);
+        }
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve(listener: TcpListener, progress: Arc<Mutex<Progress>>, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(e) = respond(stream, &progress) {
+                    // The server thread cannot reach the render's observer.
+                    eprintln!("Preview request failed: {}", e);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                eprintln!("Preview server stopped: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+fn respond(stream: TcpStream, progress: &Mutex<Progress>) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/") => ("200 OK", "text/html", PAGE.as_bytes().to_vec()),
+        ("GET", "/image.png") => {
+            // Encode a copy, so the render is not held up while it compresses.
+            let image = {
+                let mut progress = progress.lock().unwrap();
+                progress.watched = true;
+                progress.delivered = progress.told_finished;
+                progress.image.clone()
+            };
+            let mut png = Cursor::new(Vec::new());
+            image
+                .write_to(&mut png, ImageFormat::Png)
+                .map_err(io::Error::other)?;
+            ("200 OK", "image/png", png.into_inner())
+        }
+        ("GET", "/stats.json") => {
+            let mut progress = progress.lock().unwrap();
+            progress.watched = true;
+            progress.told_finished = progress.finished;
+            ("200 OK", "application/json", progress.stats().into_bytes())
+        }
+        ("GET", _) => ("404 Not Found", "text/plain", b"not found".to_vec()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            b"method not allowed".to_vec(),
+        ),
+    };
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
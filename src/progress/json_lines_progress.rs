@@ -0,0 +1,78 @@
+use std::cell::RefCell;
+use std::io::Write;
+
+use crate::progress::{Progress, ProgressObserver};
+
+/// Writes one JSON object per update, for other programs to follow a render.
+pub struct JsonLinesProgress {
+    writer: RefCell<Box<dyn Write>>,
+}
+
+impl JsonLinesProgress {
+    pub fn new(writer: impl Write + 'static) -> Self {
+        Self {
+            writer: RefCell::new(Box::new(writer)),
+        }
+    }
+
+    fn write(&self, event: &str, progress: &Progress, extra: &str) {
+        let optional = |v: Option<f64>| v.map_or("null".to_string(), |v| v.to_string());
+        let mut writer = self.writer.borrow_mut();
+        // Progress reports are best effort; a closed pipe must not stop the render.
+        let _ = writeln!(
+            writer,
+            "{{\"event\":\"{}\",\"unit\":\"{}\",\"completed\":{},\"total\":{},\"elapsed\":{},\"eta\":{},\"samples_per_second\":{},\"rays_per_second\":{}{}}}",
+            event,
+            progress.unit,
+            progress.completed,
+            progress.total,
+            progress.elapsed.as_secs_f64(),
+            progress.eta().as_secs_f64(),
+            progress.samples_per_second(),
+            optional(progress.rays_per_second()),
+            extra
+        );
+        let _ = writer.flush();
+    }
+}
+
+/// `text` as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+impl ProgressObserver for JsonLinesProgress {
+    fn update(&self, progress: &Progress) {
+        self.write("progress", progress, "");
+    }
+
+    fn finished(&self, progress: &Progress, cancelled: bool) {
+        self.write(
+            "finished",
+            progress,
+            &format!(",\"cancelled\":{}", cancelled),
+        );
+    }
+
+    fn message(&self, message: &str) {
+        let mut writer = self.writer.borrow_mut();
+        let _ = writeln!(
+            writer,
+            "{{\"event\":\"message\",\"message\":{}}}",
+            json_string(message)
+        );
+        let _ = writer.flush();
+    }
+}
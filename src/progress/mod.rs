@@ -0,0 +1,81 @@
+mod json_lines_progress;
+mod terminal_progress;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use json_lines_progress::JsonLinesProgress;
+pub use terminal_progress::TerminalProgress;
+
+/// How far a render has got, reported after every scanline or tile.
+#[derive(Copy, Clone, Debug)]
+pub struct Progress {
+    /// What is being counted, such as "scanline" or "tile".
+    pub unit: &'static str,
+    pub completed: u32,
+    pub total: u32,
+    pub samples: u64,
+    /// Rays traced so far, when the renderer can count them.
+    pub rays: Option<u64>,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    pub fn fraction(&self) -> f64 {
+        self.completed as f64 / self.total.max(1) as f64
+    }
+
+    /// Estimated time left, assuming the rest goes as fast as what is done.
+    pub fn eta(&self) -> Duration {
+        if self.completed == 0 {
+            return Duration::ZERO;
+        }
+        self.elapsed
+            .mul_f64((self.total - self.completed) as f64 / self.completed as f64)
+    }
+
+    pub fn samples_per_second(&self) -> f64 {
+        self.samples as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn rays_per_second(&self) -> Option<f64> {
+        self.rays
+            .map(|rays| rays as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON))
+    }
+}
+
+/// Receives progress from a running render.
+pub trait ProgressObserver {
+    fn update(&self, progress: &Progress);
+
+    /// Called once at the end, also when the render was cancelled.
+    fn finished(&self, progress: &Progress, cancelled: bool);
+
+    /// A notice other than progress, such as where a preview is served or a
+    /// worker joining. Goes to standard error unless overridden.
+    fn message(&self, message: &str) {
+        eprintln!("{}", message);
+    }
+}
+
+/// Stops a render early. Clones share the same state, so one can be handed to
+/// another thread or a signal handler while the camera holds the other.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,50 @@
+use std::io::{stdout, Write};
+
+use crate::progress::{Progress, ProgressObserver};
+
+/// Keeps a single status line on the terminal up to date.
+#[derive(Default)]
+pub struct TerminalProgress;
+
+impl ProgressObserver for TerminalProgress {
+    fn update(&self, progress: &Progress) {
+        let mut line = format!(
+            "\r{} {}/{} ({:.2}%), {} remaining. Time Spend: {:.2}s. Time Remaining Estimate: {:.2}s. {:.0} samples/s",
+            capitalized(progress.unit),
+            progress.completed,
+            progress.total,
+            progress.fraction() * 100.0,
+            progress.total - progress.completed,
+            progress.elapsed.as_secs_f64(),
+            progress.eta().as_secs_f64(),
+            progress.samples_per_second(),
+        );
+        if let Some(rays) = progress.rays_per_second() {
+            line += &format!(", {:.0} rays/s", rays);
+        }
+        print!("{}     ", line);
+        let _ = stdout().flush();
+    }
+
+    fn finished(&self, progress: &Progress, cancelled: bool) {
+        let outcome = if cancelled { "cancelled" } else { "done" };
+        println!(
+            "\r...{}. Total Time: {}s                                                                                                         ",
+            outcome,
+            progress.elapsed.as_secs_f64()
+        );
+    }
+
+    /// Printed over the status line, which the next update draws again below.
+    fn message(&self, message: &str) {
+        println!("\r{:<120}", message);
+    }
+}
+
+fn capitalized(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
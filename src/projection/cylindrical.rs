@@ -0,0 +1,32 @@
+use crate::error::{Error, Result};
+use crate::point3::Point3;
+use crate::projection::Projection;
+use crate::vec3::Vec3;
+
+/// A panorama on a cylinder around the up axis, covering `hfov` degrees
+/// horizontally. Vertical lines stay straight.
+pub struct Cylindrical {
+    hfov: f64,
+}
+
+impl Cylindrical {
+    pub fn new(hfov: f64) -> Result<Self> {
+        if !(hfov > 0.0 && hfov <= 360.0) {
+            return Err(Error::invalid(format!(
+                "horizontal field of view must be between 0 and 360 degrees, got {}",
+                hfov
+            )));
+        }
+        Ok(Self { hfov })
+    }
+}
+
+impl Projection for Cylindrical {
+    fn ray(&self, s: f64, t: f64, aspect_ratio: f64, _lens: (f64, f64)) -> Option<(Point3, Vec3)> {
+        let half = self.hfov.to_radians() / 2.0;
+        let phi = (2.0 * s - 1.0) * half;
+        let height = (1.0 - 2.0 * t) * half / aspect_ratio;
+        let direction = Vec3::new(phi.sin(), height, -phi.cos());
+        Some((Point3::default(), direction))
+    }
+}
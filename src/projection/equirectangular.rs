@@ -0,0 +1,23 @@
+use std::f64::consts::PI;
+
+use crate::point3::Point3;
+use crate::projection::Projection;
+use crate::vec3::Vec3;
+
+/// A full 360° by 180° panorama, longitude across and latitude down the image.
+/// Use a 2:1 aspect ratio for square pixels.
+#[derive(Default)]
+pub struct Equirectangular;
+
+impl Projection for Equirectangular {
+    fn ray(&self, s: f64, t: f64, _aspect_ratio: f64, _lens: (f64, f64)) -> Option<(Point3, Vec3)> {
+        let longitude = (2.0 * s - 1.0) * PI;
+        let latitude = (0.5 - t) * PI;
+        let direction = Vec3::new(
+            latitude.cos() * longitude.sin(),
+            latitude.sin(),
+            -latitude.cos() * longitude.cos(),
+        );
+        Some((Point3::default(), direction))
+    }
+}
@@ -0,0 +1,48 @@
+use crate::error::{Error, Result};
+use crate::point3::Point3;
+use crate::projection::Projection;
+use crate::vec3::Vec3;
+
+/// An equidistant fisheye: the angle off the view axis grows linearly with the
+/// distance from the image centre. The image circle spans `fov` degrees and
+/// fits the shorter side of the image.
+pub struct Fisheye {
+    fov: f64,
+}
+
+impl Fisheye {
+    pub fn new(fov: f64) -> Result<Self> {
+        if !(fov > 0.0 && fov <= 360.0) {
+            return Err(Error::invalid(format!(
+                "fisheye field of view must be between 0 and 360 degrees, got {}",
+                fov
+            )));
+        }
+        Ok(Self { fov })
+    }
+}
+
+impl Projection for Fisheye {
+    fn ray(&self, s: f64, t: f64, aspect_ratio: f64, _lens: (f64, f64)) -> Option<(Point3, Vec3)> {
+        let (mut x, mut y) = (2.0 * s - 1.0, 1.0 - 2.0 * t);
+        if aspect_ratio >= 1.0 {
+            x *= aspect_ratio;
+        } else {
+            y /= aspect_ratio;
+        }
+
+        let r = (x * x + y * y).sqrt();
+        if r > 1.0 {
+            return None;
+        }
+
+        let theta = r * self.fov.to_radians() / 2.0;
+        let phi = y.atan2(x);
+        let direction = Vec3::new(
+            theta.sin() * phi.cos(),
+            theta.sin() * phi.sin(),
+            -theta.cos(),
+        );
+        Some((Point3::default(), direction))
+    }
+}
@@ -0,0 +1,35 @@
+mod cylindrical;
+mod equirectangular;
+mod fisheye;
+mod orthographic;
+mod perspective;
+mod realistic;
+mod stereo;
+
+use crate::point3::Point3;
+use crate::vec3::Vec3;
+
+pub use cylindrical::Cylindrical;
+pub use equirectangular::Equirectangular;
+pub use fisheye::Fisheye;
+pub use orthographic::Orthographic;
+pub use perspective::Perspective;
+pub use realistic::{LensElement, RealisticLens};
+pub use stereo::Stereo;
+
+/// Maps positions on the image to camera rays.
+///
+/// Rays are in camera space: `x` to the right, `y` up and looking down `-z`.
+/// The camera focuses at `origin + focus_dist * direction`, so projections
+/// returning unit directions focus on a sphere around the camera.
+pub trait Projection {
+    /// The ray through `(s, t)`, measured from the top left corner of the image
+    /// in `[0, 1]`, or `None` if that point of the image sees nothing. `lens` is
+    /// a uniform 2D sample for projections which model a lens.
+    fn ray(&self, s: f64, t: f64, aspect_ratio: f64, lens: (f64, f64)) -> Option<(Point3, Vec3)>;
+
+    /// Scale applied to every sample, for projections which lose light.
+    fn exposure(&self) -> f64 {
+        1.0
+    }
+}
@@ -0,0 +1,28 @@
+use crate::error::{Error, Result};
+use crate::point3::Point3;
+use crate::projection::Projection;
+use crate::vec3::Vec3;
+
+/// Parallel rays; `height` is the height of the view in world units.
+pub struct Orthographic {
+    height: f64,
+}
+
+impl Orthographic {
+    pub fn new(height: f64) -> Result<Self> {
+        Error::check_positive("orthographic view height", height)?;
+        Ok(Self { height })
+    }
+}
+
+impl Projection for Orthographic {
+    fn ray(&self, s: f64, t: f64, aspect_ratio: f64, _lens: (f64, f64)) -> Option<(Point3, Vec3)> {
+        let half = self.height / 2.0;
+        let origin = Point3::new(
+            (2.0 * s - 1.0) * half * aspect_ratio,
+            (1.0 - 2.0 * t) * half,
+            0.0,
+        );
+        Some((origin, Vec3::new(0.0, 0.0, -1.0)))
+    }
+}
@@ -0,0 +1,33 @@
+use crate::error::{Error, Result};
+use crate::point3::Point3;
+use crate::projection::Projection;
+use crate::vec3::Vec3;
+
+/// A pinhole projection with a vertical field of view in degrees.
+pub struct Perspective {
+    vfov: f64,
+}
+
+impl Perspective {
+    pub fn new(vfov: f64) -> Result<Self> {
+        if !(vfov > 0.0 && vfov < 180.0) {
+            return Err(Error::invalid(format!(
+                "vertical field of view must be between 0 and 180 degrees, got {}",
+                vfov
+            )));
+        }
+        Ok(Self { vfov })
+    }
+}
+
+impl Projection for Perspective {
+    fn ray(&self, s: f64, t: f64, aspect_ratio: f64, _lens: (f64, f64)) -> Option<(Point3, Vec3)> {
+        let h = (self.vfov.to_radians() / 2.0).tan();
+        let direction = Vec3::new(
+            (2.0 * s - 1.0) * h * aspect_ratio,
+            (1.0 - 2.0 * t) * h,
+            -1.0,
+        );
+        Some((Point3::default(), direction))
+    }
+}
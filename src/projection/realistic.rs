@@ -0,0 +1,449 @@
+use std::f64::consts::PI;
+use std::fs::read_to_string;
+use std::ops;
+
+use crate::error::{Error, Result};
+use crate::point3::Point3;
+use crate::projection::Projection;
+use crate::vec3::Vec3;
+
+const PUPIL_RADII: usize = 16;
+const PUPIL_GRID: usize = 32;
+const FOCUS_ITERATIONS: usize = 32;
+
+/// One spherical surface of a lens prescription, in millimetres.
+#[derive(Copy, Clone, Debug)]
+pub struct LensElement {
+    /// Positive if the centre of curvature lies towards the film. Zero marks
+    /// the aperture stop.
+    pub curvature_radius: f64,
+    /// Distance along the axis to the next surface.
+    pub thickness: f64,
+    /// Index of refraction behind the surface, towards the film. Zero is air.
+    pub ior: f64,
+    /// Diameter of the surface.
+    pub aperture: f64,
+}
+
+impl LensElement {
+    fn ior(&self) -> f64 {
+        if self.ior == 0.0 {
+            1.0
+        } else {
+            self.ior
+        }
+    }
+}
+
+/// Traces camera rays through a lens prescription, giving real distortion,
+/// focus breathing and optical vignetting. The film sits at the camera
+/// position with the lens in front of it.
+///
+/// Lens space has the film at `z = 0` and the scene towards `+z`.
+#[derive(Clone)]
+pub struct RealisticLens {
+    elements: Vec<LensElement>,
+    film_diagonal: f64,
+    units_per_mm: f64,
+    film_distance: f64,
+    vertices: Vec<f64>,
+    pupil_radius: f64,
+    exposure: f64,
+}
+
+impl RealisticLens {
+    /// Elements from the front of the lens to the back. The thickness of the
+    /// last one is the initial distance to the film.
+    pub fn new(elements: Vec<LensElement>) -> Result<Self> {
+        if elements.is_empty() {
+            return Err(Error::invalid("a lens needs at least one element"));
+        }
+        for element in &elements {
+            Error::check_positive("lens element aperture", element.aperture)?;
+            if element.ior < 0.0 {
+                return Err(Error::invalid(format!(
+                    "lens element index of refraction must not be negative, got {}",
+                    element.ior
+                )));
+            }
+        }
+        Ok(Self::from_elements(elements))
+    }
+
+    fn from_elements(elements: Vec<LensElement>) -> Self {
+        let film_distance = elements[elements.len() - 1].thickness;
+        let mut lens = Self {
+            elements,
+            film_diagonal: 35.0,
+            units_per_mm: 0.001,
+            film_distance,
+            vertices: Vec::new(),
+            pupil_radius: 0.0,
+            exposure: 1.0,
+        };
+        lens.update();
+        lens
+    }
+
+    /// Reads a prescription with one element per line: curvature radius,
+    /// thickness, index of refraction and aperture diameter. Lines starting
+    /// with `#` are comments.
+    pub fn load(filename: &str) -> Result<Self> {
+        let mut elements = Vec::new();
+        for line in read_to_string(filename)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let values = line
+                .split_whitespace()
+                .map(|v| v.parse::<f64>())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| Error::invalid(format!("{} in lens element: {}", e, line)))?;
+            let [curvature_radius, thickness, ior, aperture] = values[..] else {
+                return Err(Error::invalid(format!(
+                    "expected 4 values per lens element, got: {}",
+                    line
+                )));
+            };
+            elements.push(LensElement {
+                curvature_radius,
+                thickness,
+                ior,
+                aperture,
+            });
+        }
+
+        Self::new(elements)
+    }
+
+    /// Tronnier's f/2 double Gauss (US patent 2,673,491), scaled to 50 mm.
+    pub fn double_gauss_50mm() -> Self {
+        let element = |curvature_radius, thickness, ior, aperture| LensElement {
+            curvature_radius,
+            thickness,
+            ior,
+            aperture,
+        };
+        Self::from_elements(vec![
+            element(29.475, 3.76, 1.67, 25.2),
+            element(84.83, 0.12, 1.0, 25.2),
+            element(19.275, 4.025, 1.67, 23.0),
+            element(40.77, 3.275, 1.699, 23.0),
+            element(12.75, 5.705, 1.0, 18.0),
+            element(0.0, 4.5, 0.0, 17.1),
+            element(-14.495, 1.18, 1.603, 17.0),
+            element(40.77, 6.065, 1.658, 20.0),
+            element(-20.385, 0.19, 1.0, 20.0),
+            element(437.065, 3.22, 1.717, 20.0),
+            element(-39.73, 0.0, 1.0, 20.0),
+        ])
+    }
+
+    /// Film diagonal in millimetres; 35 by default.
+    pub fn film_diagonal(mut self, film_diagonal: f64) -> Self {
+        self.film_diagonal = film_diagonal;
+        self.update();
+        self
+    }
+
+    /// Size of a millimetre in scene units; by default a scene unit is a metre.
+    pub fn units_per_mm(mut self, units_per_mm: f64) -> Self {
+        self.units_per_mm = units_per_mm;
+        self
+    }
+
+    /// Moves the lens group so that objects `focus_dist` scene units in front
+    /// of the film are sharp. Leaves the lens alone if it cannot focus there.
+    pub fn focused(mut self, focus_dist: f64) -> Self {
+        let start = self.film_distance;
+        let object_z = focus_dist / self.units_per_mm;
+        let height = self.elements[0].aperture / 2.0 * 0.01;
+
+        for _ in 0..FOCUS_ITERATIONS {
+            let origin = LensVector::new(0.0, 0.0, object_z);
+            let target = LensVector::new(height, 0.0, self.vertices[0]);
+            let Some((p, d)) = self.trace_from_scene(origin, (target - origin).unit_vector())
+            else {
+                break;
+            };
+            if d.x().abs() < 1e-12 {
+                break;
+            }
+
+            let image_z = p.z() - p.x() / d.x() * d.z();
+            self.film_distance -= image_z;
+            self.update();
+            if image_z.abs() < 1e-6 {
+                return self;
+            }
+            if self.film_distance <= 0.0 || self.vertices[0] >= object_z {
+                break;
+            }
+        }
+
+        self.film_distance = start;
+        self.update();
+        self
+    }
+
+    fn update(&mut self) {
+        let mut z = self.film_distance;
+        self.vertices = vec![0.0; self.elements.len()];
+        for i in (0..self.elements.len()).rev() {
+            if i + 1 < self.elements.len() {
+                z += self.elements[i].thickness;
+            }
+            self.vertices[i] = z;
+        }
+        self.update_pupil();
+    }
+
+    /// Where a ray meets surface `i`, and the surface normal there.
+    fn intersect(
+        &self,
+        i: usize,
+        origin: LensVector,
+        direction: LensVector,
+    ) -> Option<(LensVector, LensVector)> {
+        let element = &self.elements[i];
+        let z = self.vertices[i];
+        let radius = element.curvature_radius;
+
+        let (p, normal) = if radius == 0.0 {
+            let t = (z - origin.z()) / direction.z();
+            if t <= 0.0 {
+                return None;
+            }
+            (origin + t * direction, LensVector::new(0.0, 0.0, 1.0))
+        } else {
+            let center = LensVector::new(0.0, 0.0, z - radius);
+            let oc = origin - center;
+            let half_b = oc.dot(&direction);
+            let c = oc.length_squared() - radius * radius;
+            let discriminant = half_b * half_b - c;
+            if discriminant < 0.0 {
+                return None;
+            }
+
+            let far = (radius > 0.0) == (direction.z() > 0.0);
+            let t = if far {
+                -half_b + discriminant.sqrt()
+            } else {
+                -half_b - discriminant.sqrt()
+            };
+            if t <= 0.0 {
+                return None;
+            }
+            let p = origin + t * direction;
+            (p, (p - center) / radius)
+        };
+
+        let r = element.aperture / 2.0;
+        if p.x() * p.x() + p.y() * p.y() > r * r {
+            return None;
+        }
+        Some((p, normal))
+    }
+
+    fn refract(direction: LensVector, normal: LensVector, eta: f64) -> Option<LensVector> {
+        let normal = if normal.dot(&direction) > 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        let cos_i = -normal.dot(&direction);
+        let sin2_t = eta * eta * (1.0 - cos_i * cos_i);
+        if sin2_t > 1.0 {
+            return None;
+        }
+        let cos_t = (1.0 - sin2_t).sqrt();
+        Some((eta * direction + (eta * cos_i - cos_t) * normal).unit_vector())
+    }
+
+    /// Index of refraction in front of surface `i`.
+    fn ior_before(&self, i: usize) -> f64 {
+        if i == 0 {
+            1.0
+        } else {
+            self.elements[i - 1].ior()
+        }
+    }
+
+    fn trace_from_film(
+        &self,
+        mut origin: LensVector,
+        mut direction: LensVector,
+    ) -> Option<(LensVector, LensVector)> {
+        for i in (0..self.elements.len()).rev() {
+            let (p, normal) = self.intersect(i, origin, direction)?;
+            origin = p;
+            if self.elements[i].curvature_radius != 0.0 {
+                let eta = self.elements[i].ior() / self.ior_before(i);
+                direction = Self::refract(direction, normal, eta)?;
+            }
+        }
+        Some((origin, direction))
+    }
+
+    fn trace_from_scene(
+        &self,
+        mut origin: LensVector,
+        mut direction: LensVector,
+    ) -> Option<(LensVector, LensVector)> {
+        for i in 0..self.elements.len() {
+            let (p, normal) = self.intersect(i, origin, direction)?;
+            origin = p;
+            if self.elements[i].curvature_radius != 0.0 {
+                let eta = self.ior_before(i) / self.elements[i].ior();
+                direction = Self::refract(direction, normal, eta)?;
+            }
+        }
+        Some((origin, direction))
+    }
+
+    /// Finds the disk on the rear element through which light reaches any
+    /// part of the film, and the exposure which keeps the image centre as
+    /// bright as a pinhole would.
+    fn update_pupil(&mut self) {
+        let rear = self.elements.len() - 1;
+        let r = self.elements[rear].aperture / 2.0;
+        let z = self.vertices[rear];
+        let cell = 2.0 * r / PUPIL_GRID as f64;
+
+        let mut radius: f64 = 0.0;
+        let mut on_axis = 0;
+        for k in 0..=PUPIL_RADII {
+            let film_x = k as f64 / PUPIL_RADII as f64 * self.film_diagonal / 2.0;
+            let origin = LensVector::new(film_x, 0.0, 0.0);
+            for i in 0..PUPIL_GRID {
+                for j in 0..PUPIL_GRID {
+                    let x = -r + (i as f64 + 0.5) * cell;
+                    let y = -r + (j as f64 + 0.5) * cell;
+                    let direction = (LensVector::new(x, y, z) - origin).unit_vector();
+                    if self.trace_from_film(origin, direction).is_some() {
+                        radius = radius.max((x * x + y * y).sqrt());
+                        if k == 0 {
+                            on_axis += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        self.pupil_radius = (radius + cell).min(r);
+        self.exposure = if on_axis > 0 {
+            PI * self.pupil_radius * self.pupil_radius / (on_axis as f64 * cell * cell)
+        } else {
+            1.0
+        };
+    }
+}
+
+impl Projection for RealisticLens {
+    fn ray(&self, s: f64, t: f64, aspect_ratio: f64, lens: (f64, f64)) -> Option<(Point3, Vec3)> {
+        // The lens inverts the image, so the film is read mirrored.
+        let height = self.film_diagonal / (1.0 + aspect_ratio * aspect_ratio).sqrt();
+        let width = aspect_ratio * height;
+        let film = LensVector::new(
+            -(2.0 * s - 1.0) * width / 2.0,
+            (2.0 * t - 1.0) * height / 2.0,
+            0.0,
+        );
+
+        let aperture = self.pupil_radius * Vec3::sample_in_unit_disk(lens);
+        let rear = LensVector::new(
+            aperture.x(),
+            aperture.y(),
+            self.vertices[self.vertices.len() - 1],
+        );
+
+        let (p, d) = self.trace_from_film(film, (rear - film).unit_vector())?;
+        let scale = self.units_per_mm;
+        Some((
+            Point3::new(p.x() * scale, p.y() * scale, -p.z() * scale),
+            Vec3::new(d.x(), d.y(), -d.z()),
+        ))
+    }
+
+    fn exposure(&self) -> f64 {
+        self.exposure
+    }
+}
+
+/// A point or direction in lens space. Always double precision, whatever the
+/// `f32` feature picks for [`Vec3`]: focusing resolves microns through
+/// surfaces tens of millimetres apart, which single precision cannot.
+#[derive(Copy, Clone, Debug)]
+struct LensVector([f64; 3]);
+
+impl LensVector {
+    fn new(x: f64, y: f64, z: f64) -> Self {
+        Self([x, y, z])
+    }
+
+    fn x(&self) -> f64 {
+        self.0[0]
+    }
+
+    fn y(&self) -> f64 {
+        self.0[1]
+    }
+
+    fn z(&self) -> f64 {
+        self.0[2]
+    }
+
+    fn dot(&self, rhs: &Self) -> f64 {
+        self.x() * rhs.x() + self.y() * rhs.y() + self.z() * rhs.z()
+    }
+
+    fn length_squared(&self) -> f64 {
+        self.dot(self)
+    }
+
+    fn unit_vector(self) -> Self {
+        self / self.length_squared().sqrt()
+    }
+}
+
+impl ops::Add for LensVector {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl ops::Sub for LensVector {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl ops::Neg for LensVector {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.map(|v| -v))
+    }
+}
+
+impl ops::Mul<LensVector> for f64 {
+    type Output = LensVector;
+
+    fn mul(self, rhs: LensVector) -> LensVector {
+        LensVector(rhs.0.map(|v| self * v))
+    }
+}
+
+impl ops::Div<f64> for LensVector {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Self(self.0.map(|v| v / rhs))
+    }
+}
@@ -0,0 +1,39 @@
+use std::rc::Rc;
+
+use crate::point3::Point3;
+use crate::projection::Projection;
+use crate::vec3::Vec3;
+
+/// Side-by-side stereo pair: the left half of the image is seen by the left eye
+/// and the right half by the right eye, with parallel view axes `eye_separation`
+/// apart.
+pub struct Stereo {
+    projection: Rc<dyn Projection>,
+    eye_separation: f64,
+}
+
+impl Stereo {
+    pub fn new(projection: Rc<dyn Projection>, eye_separation: f64) -> Self {
+        Self {
+            projection,
+            eye_separation,
+        }
+    }
+}
+
+impl Projection for Stereo {
+    fn ray(&self, s: f64, t: f64, aspect_ratio: f64, lens: (f64, f64)) -> Option<(Point3, Vec3)> {
+        let (s, eye) = if s < 0.5 {
+            (2.0 * s, -0.5)
+        } else {
+            (2.0 * s - 1.0, 0.5)
+        };
+        let (origin, direction) = self.projection.ray(s, t, aspect_ratio / 2.0, lens)?;
+        let offset = Vec3::new(eye * self.eye_separation, 0.0, 0.0);
+        Some((origin + offset, direction))
+    }
+
+    fn exposure(&self) -> f64 {
+        self.projection.exposure()
+    }
+}
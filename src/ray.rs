@@ -1,34 +1,69 @@
 use crate::point3::Point3;
+use crate::simd::F3;
 use crate::vec3::Vec3;
 
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Ray {
     origin: Point3,
     direction: Vec3,
+    /// `1 / direction` per component, for slab tests.
+    inv_direction: Vec3,
     time: f64,
+    wavelength: Option<f64>,
+    alpha_sample: f64,
 }
 
 impl Ray {
-    pub(crate) fn new(origin: Point3, direction: Vec3, time: f64) -> Self {
+    #[inline]
+    pub fn new(origin: Point3, direction: Vec3, time: f64) -> Self {
         Self {
             origin,
             direction,
+            inv_direction: Vec3::from_lanes(F3::scalar(1.0) / direction.lanes()),
             time,
+            wavelength: None,
+            alpha_sample: 0.5,
         }
     }
 
-    pub(crate) fn origin(&self) -> Point3 {
+    pub fn with_wavelength(mut self, wavelength: Option<f64>) -> Self {
+        self.wavelength = wavelength;
+        self
+    }
+
+    pub fn with_alpha_sample(mut self, alpha_sample: f64) -> Self {
+        self.alpha_sample = alpha_sample;
+        self
+    }
+
+    pub fn origin(&self) -> Point3 {
         self.origin
     }
 
-    pub(crate) fn direction(&self) -> Vec3 {
+    pub fn direction(&self) -> Vec3 {
         self.direction
     }
+    pub fn inv_direction(&self) -> Vec3 {
+        self.inv_direction
+    }
+
     pub fn time(&self) -> f64 {
         self.time
     }
 
-    pub(crate) fn at(&self, t: f64) -> Point3 {
-        &self.origin + t * self.direction
+    /// The wavelength in nanometres carried by the ray in spectral mode.
+    pub fn wavelength(&self) -> Option<f64> {
+        self.wavelength
+    }
+
+    /// The number in `[0, 1)` which partially transparent surfaces hit by the
+    /// ray compare their opacity with, from the camera's sampler. One half for
+    /// rays which were not given one.
+    pub fn alpha_sample(&self) -> f64 {
+        self.alpha_sample
+    }
+
+    pub fn at(&self, t: f64) -> Point3 {
+        self.origin + t * self.direction
     }
 }
@@ -0,0 +1,53 @@
+use crate::sampler::{hash, hash_id, to_unit, SampleId, Sampler};
+
+const PRIMES: usize = 1024;
+
+/// The Halton sequence, one prime base per dimension, with a random toroidal
+/// shift per pixel and dimension. Dimensions past the prime table fall back to
+/// independent random numbers.
+pub struct HaltonSampler {
+    seed: u64,
+    primes: Vec<u64>,
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> Self {
+        let mut primes = Vec::with_capacity(PRIMES);
+        let mut candidate = 2;
+        while primes.len() < PRIMES {
+            if primes
+                .iter()
+                .take_while(|p| *p * *p <= candidate)
+                .all(|p| candidate % p != 0)
+            {
+                primes.push(candidate);
+            }
+            candidate += 1;
+        }
+        Self { seed, primes }
+    }
+
+    fn radical_inverse(base: u64, mut a: u64) -> f64 {
+        let inverse_base = 1.0 / base as f64;
+        let mut reversed = 0.0;
+        let mut scale = inverse_base;
+        while a > 0 {
+            reversed += (a % base) as f64 * scale;
+            a /= base;
+            scale *= inverse_base;
+        }
+        reversed
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn get_1d(&self, id: SampleId) -> f64 {
+        let shift = to_unit(hash_id(self.seed, id));
+        let Some(&base) = self.primes.get(id.dimension as usize) else {
+            return to_unit(hash(self.seed, &[shift.to_bits(), id.index as u64]));
+        };
+
+        let value = Self::radical_inverse(base, id.index as u64) + shift;
+        value - value.floor()
+    }
+}
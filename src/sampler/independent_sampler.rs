@@ -0,0 +1,26 @@
+use crate::sampler::{hash, to_unit, SampleId, Sampler};
+
+/// Uncorrelated random numbers for every dimension of every sample.
+pub struct IndependentSampler {
+    seed: u64,
+}
+
+impl IndependentSampler {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn get_1d(&self, id: SampleId) -> f64 {
+        to_unit(hash(
+            self.seed,
+            &[
+                id.pixel.0 as u64,
+                id.pixel.1 as u64,
+                id.index as u64,
+                id.dimension as u64,
+            ],
+        ))
+    }
+}
@@ -0,0 +1,163 @@
+mod halton_sampler;
+mod independent_sampler;
+mod sobol_sampler;
+mod stratified_sampler;
+
+pub use halton_sampler::HaltonSampler;
+pub use independent_sampler::IndependentSampler;
+pub use sobol_sampler::SobolSampler;
+pub use stratified_sampler::StratifiedSampler;
+
+/// Dimensions used by the camera: pixel position, lens, time and wavelength.
+const CAMERA_DIMENSIONS: u32 = 8;
+/// Dimensions reserved for the decisions made at each bounce, the last of
+/// which is kept for alpha tests along the bounce's ray.
+const BOUNCE_DIMENSIONS: u32 = 4;
+const ALPHA_DIMENSION: u32 = BOUNCE_DIMENSIONS - 1;
+/// Where the dimensions for retrying rejected samples start, far past those
+/// of any bounce.
+const RETRY_DIMENSIONS: u32 = 1 << 24;
+
+/// Identifies one random number: the pixel, which of its `count` samples it
+/// belongs to, and the dimension within that sample.
+#[derive(Copy, Clone, Debug)]
+pub struct SampleId {
+    pub pixel: (i32, i32),
+    pub index: u32,
+    pub count: u32,
+    pub dimension: u32,
+}
+
+/// Provides the random numbers for rendering, in `[0, 1)`. Samplers are
+/// deterministic for a given seed, so any sample can be reproduced.
+pub trait Sampler {
+    fn get_1d(&self, id: SampleId) -> f64;
+
+    /// Two dimensions at once, `id.dimension` and the one after it.
+    fn get_2d(&self, id: SampleId) -> (f64, f64) {
+        let next = SampleId {
+            dimension: id.dimension + 1,
+            ..id
+        };
+        (self.get_1d(id), self.get_1d(next))
+    }
+}
+
+/// The dimensions of one pixel sample, handed out in order.
+pub struct SampleStream<'a> {
+    sampler: &'a dyn Sampler,
+    id: SampleId,
+}
+
+impl<'a> SampleStream<'a> {
+    pub fn new(sampler: &'a dyn Sampler, pixel: (i32, i32), index: u32, count: u32) -> Self {
+        Self {
+            sampler,
+            id: SampleId {
+                pixel,
+                index,
+                count,
+                dimension: 0,
+            },
+        }
+    }
+
+    /// Moves on to the dimensions reserved for `bounce`, so every bounce sees
+    /// the same dimensions no matter how many the previous ones used.
+    pub fn start_bounce(&mut self, bounce: u32) {
+        self.id.dimension = CAMERA_DIMENSIONS + bounce * BOUNCE_DIMENSIONS;
+    }
+
+    pub fn get_1d(&mut self) -> f64 {
+        let value = self.sampler.get_1d(self.id);
+        self.id.dimension += 1;
+        value
+    }
+
+    pub fn get_2d(&mut self) -> (f64, f64) {
+        let value = self.sampler.get_2d(self.id);
+        self.id.dimension += 2;
+        value
+    }
+
+    /// The number which alpha tests along the ray of `bounce` compare
+    /// opacities with. Leaves the stream where it is.
+    pub fn alpha_sample(&self, bounce: u32) -> f64 {
+        self.sampler.get_1d(SampleId {
+            dimension: CAMERA_DIMENSIONS + bounce * BOUNCE_DIMENSIONS + ALPHA_DIMENSION,
+            ..self.id
+        })
+    }
+
+    /// Point `k` for retrying a rejected two dimensional sample, such as a
+    /// point on the lens. Leaves the stream where it is.
+    pub fn retry_2d(&self, k: u32) -> (f64, f64) {
+        self.sampler.get_2d(SampleId {
+            dimension: RETRY_DIMENSIONS + 2 * k,
+            ..self.id
+        })
+    }
+}
+
+fn mix(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58476d1ce4e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Hashes a seed and the parts of a sample id to 64 random looking bits.
+pub(crate) fn hash(seed: u64, values: &[u64]) -> u64 {
+    values.iter().fold(
+        mix(seed),
+        |h, v| mix(h ^ v.wrapping_add(0x9e3779b97f4a7c15)),
+    )
+}
+
+pub(crate) fn hash_id(seed: u64, id: SampleId) -> u64 {
+    hash(
+        seed,
+        &[id.pixel.0 as u64, id.pixel.1 as u64, id.dimension as u64],
+    )
+}
+
+/// A uniform value in `[0, 1)` from hashed bits.
+pub(crate) fn to_unit(bits: u64) -> f64 {
+    (bits >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+/// Element `i` of a random permutation of `0..l` selected by `p`, without
+/// building the permutation (Kensler, "Correlated Multi-Jittered Sampling").
+pub(crate) fn permutation_element(mut i: u32, l: u32, p: u32) -> u32 {
+    let mut w = l - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        i ^= p;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= p >> 16;
+        i ^= (i & w) >> 4;
+        i ^= p >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= p >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | p >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < l {
+            break;
+        }
+    }
+    i.wrapping_add(p) % l
+}
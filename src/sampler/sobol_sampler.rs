@@ -0,0 +1,68 @@
+use crate::sampler::{hash_id, SampleId, Sampler};
+
+/// The first two Sobol dimensions, Owen scrambled and shuffled independently
+/// for every pixel and pair of dimensions (Burley, "Practical Hash-based Owen
+/// Scrambling", 2020).
+pub struct SobolSampler {
+    seed: u64,
+}
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+        x = x.wrapping_add(seed);
+        x ^= x.wrapping_mul(0x6c50b47c);
+        x ^= x.wrapping_mul(0xb82f1e52);
+        x ^= x.wrapping_mul(0xc7afe638);
+        x ^= x.wrapping_mul(0x8d22f6e6);
+        x
+    }
+
+    fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+        Self::laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+    }
+
+    /// The second Sobol dimension, whose direction numbers are the rows of
+    /// Pascal's triangle modulo two.
+    fn sobol_1(mut index: u32) -> u32 {
+        let mut result = 0;
+        let mut v = 1 << 31;
+        while index != 0 {
+            if index & 1 != 0 {
+                result ^= v;
+            }
+            index >>= 1;
+            v ^= v >> 1;
+        }
+        result
+    }
+
+    fn seeds(&self, id: SampleId) -> [u32; 3] {
+        let h = hash_id(self.seed, id);
+        [h as u32, (h >> 32) as u32, (h >> 16) as u32 ^ 0x5bd1e995]
+    }
+
+    fn to_unit(x: u32) -> f64 {
+        x as f64 / (1u64 << 32) as f64
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn get_1d(&self, id: SampleId) -> f64 {
+        let [shuffle, x, _] = self.seeds(id);
+        let index = Self::nested_uniform_scramble(id.index, shuffle);
+        Self::to_unit(Self::nested_uniform_scramble(index.reverse_bits(), x))
+    }
+
+    fn get_2d(&self, id: SampleId) -> (f64, f64) {
+        let [shuffle, x, y] = self.seeds(id);
+        let index = Self::nested_uniform_scramble(id.index, shuffle);
+        (
+            Self::to_unit(Self::nested_uniform_scramble(index.reverse_bits(), x)),
+            Self::to_unit(Self::nested_uniform_scramble(Self::sobol_1(index), y)),
+        )
+    }
+}
@@ -0,0 +1,52 @@
+use crate::sampler::{hash, hash_id, permutation_element, to_unit, SampleId, Sampler};
+
+/// Jittered sampling: each dimension is split into as many strata as there
+/// are samples per pixel, and 2D requests into a square grid. Strata are
+/// visited in a different random order for every pixel and dimension.
+pub struct StratifiedSampler {
+    seed: u64,
+}
+
+impl StratifiedSampler {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    fn jitter(&self, id: SampleId, salt: u64) -> f64 {
+        to_unit(hash(
+            self.seed ^ salt,
+            &[
+                id.pixel.0 as u64,
+                id.pixel.1 as u64,
+                id.index as u64,
+                id.dimension as u64,
+            ],
+        ))
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn get_1d(&self, id: SampleId) -> f64 {
+        if id.index >= id.count {
+            return self.jitter(id, 0);
+        }
+
+        let p = hash_id(self.seed, id) as u32;
+        let stratum = permutation_element(id.index, id.count, p);
+        (stratum as f64 + self.jitter(id, 0)) / id.count as f64
+    }
+
+    fn get_2d(&self, id: SampleId) -> (f64, f64) {
+        let n = (id.count as f64).sqrt() as u32;
+        if id.index >= n * n {
+            return (self.jitter(id, 0), self.jitter(id, 1));
+        }
+
+        let p = hash_id(self.seed, id) as u32;
+        let cell = permutation_element(id.index, n * n, p);
+        (
+            ((cell % n) as f64 + self.jitter(id, 0)) / n as f64,
+            ((cell / n) as f64 + self.jitter(id, 1)) / n as f64,
+        )
+    }
+}
@@ -0,0 +1,246 @@
+use crate::point3::Point3;
+use crate::sdf::Sdf;
+use crate::vec3::Vec3;
+use std::rc::Rc;
+
+fn mix(a: f64, b: f64, h: f64) -> f64 {
+    a * (1.0 - h) + b * h
+}
+
+pub struct Union {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+}
+
+impl Union {
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>) -> Self {
+        Self { a, b }
+    }
+}
+
+impl Sdf for Union {
+    fn distance(&self, p: &Point3) -> f64 {
+        self.a.distance(p).min(self.b.distance(p))
+    }
+}
+
+pub struct Intersection {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+}
+
+impl Intersection {
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>) -> Self {
+        Self { a, b }
+    }
+}
+
+impl Sdf for Intersection {
+    fn distance(&self, p: &Point3) -> f64 {
+        self.a.distance(p).max(self.b.distance(p))
+    }
+}
+
+/// `a` with `b` cut out of it.
+pub struct Difference {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+}
+
+impl Difference {
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>) -> Self {
+        Self { a, b }
+    }
+}
+
+impl Sdf for Difference {
+    fn distance(&self, p: &Point3) -> f64 {
+        self.a.distance(p).max(-self.b.distance(p))
+    }
+}
+
+/// A union blending the shapes together over a distance of `k`.
+pub struct SmoothUnion {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+    k: f64,
+}
+
+impl SmoothUnion {
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>, k: f64) -> Self {
+        Self { a, b, k }
+    }
+}
+
+impl Sdf for SmoothUnion {
+    fn distance(&self, p: &Point3) -> f64 {
+        let (a, b) = (self.a.distance(p), self.b.distance(p));
+        let h = (0.5 + 0.5 * (b - a) / self.k).clamp(0.0, 1.0);
+        mix(b, a, h) - self.k * h * (1.0 - h)
+    }
+}
+
+pub struct SmoothIntersection {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+    k: f64,
+}
+
+impl SmoothIntersection {
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>, k: f64) -> Self {
+        Self { a, b, k }
+    }
+}
+
+impl Sdf for SmoothIntersection {
+    fn distance(&self, p: &Point3) -> f64 {
+        let (a, b) = (self.a.distance(p), self.b.distance(p));
+        let h = (0.5 - 0.5 * (b - a) / self.k).clamp(0.0, 1.0);
+        mix(b, a, h) + self.k * h * (1.0 - h)
+    }
+}
+
+pub struct SmoothDifference {
+    a: Rc<dyn Sdf>,
+    b: Rc<dyn Sdf>,
+    k: f64,
+}
+
+impl SmoothDifference {
+    pub fn new(a: Rc<dyn Sdf>, b: Rc<dyn Sdf>, k: f64) -> Self {
+        Self { a, b, k }
+    }
+}
+
+impl Sdf for SmoothDifference {
+    fn distance(&self, p: &Point3) -> f64 {
+        let (a, b) = (self.a.distance(p), self.b.distance(p));
+        let h = (0.5 - 0.5 * (a + b) / self.k).clamp(0.0, 1.0);
+        mix(a, -b, h) + self.k * h * (1.0 - h)
+    }
+}
+
+pub struct Translate {
+    sdf: Rc<dyn Sdf>,
+    offset: Vec3,
+}
+
+impl Translate {
+    pub fn new(sdf: Rc<dyn Sdf>, offset: Vec3) -> Self {
+        Self { sdf, offset }
+    }
+}
+
+impl Sdf for Translate {
+    fn distance(&self, p: &Point3) -> f64 {
+        self.sdf.distance(&(*p - self.offset))
+    }
+}
+
+/// Uniform scaling about the origin.
+pub struct Scale {
+    sdf: Rc<dyn Sdf>,
+    factor: f64,
+}
+
+impl Scale {
+    pub fn new(sdf: Rc<dyn Sdf>, factor: f64) -> Self {
+        Self { sdf, factor }
+    }
+}
+
+impl Sdf for Scale {
+    fn distance(&self, p: &Point3) -> f64 {
+        self.sdf.distance(&((1.0 / self.factor) * p)) * self.factor
+    }
+}
+
+/// Rotation about the origin by `angle` degrees around `axis`.
+pub struct Rotate {
+    sdf: Rc<dyn Sdf>,
+    axis: Vec3,
+    sin_theta: f64,
+    cos_theta: f64,
+}
+
+impl Rotate {
+    pub fn new(sdf: Rc<dyn Sdf>, axis: Vec3, angle: f64) -> Self {
+        let theta = angle.to_radians();
+        Self {
+            sdf,
+            axis: axis.unit_vector(),
+            sin_theta: theta.sin(),
+            cos_theta: theta.cos(),
+        }
+    }
+}
+
+impl Sdf for Rotate {
+    fn distance(&self, p: &Point3) -> f64 {
+        // Rodrigues' formula with the inverse rotation.
+        let v: Vec3 = (*p).into();
+        let k = self.axis;
+        let rotated = self.cos_theta * v - self.sin_theta * k.cross(&v)
+            + (1.0 - self.cos_theta) * k.dot(&v) * k;
+        self.sdf.distance(&rotated.into())
+    }
+}
+
+/// Rounds off edges by inflating the shape by `radius`.
+pub struct Round {
+    sdf: Rc<dyn Sdf>,
+    radius: f64,
+}
+
+impl Round {
+    pub fn new(sdf: Rc<dyn Sdf>, radius: f64) -> Self {
+        Self { sdf, radius }
+    }
+}
+
+impl Sdf for Round {
+    fn distance(&self, p: &Point3) -> f64 {
+        self.sdf.distance(p) - self.radius
+    }
+}
+
+/// A shell of `thickness` around the surface.
+pub struct Onion {
+    sdf: Rc<dyn Sdf>,
+    thickness: f64,
+}
+
+impl Onion {
+    pub fn new(sdf: Rc<dyn Sdf>, thickness: f64) -> Self {
+        Self { sdf, thickness }
+    }
+}
+
+impl Sdf for Onion {
+    fn distance(&self, p: &Point3) -> f64 {
+        self.sdf.distance(p).abs() - self.thickness
+    }
+}
+
+/// Infinite repetition of the shape with the given period along each axis.
+pub struct Repeat {
+    sdf: Rc<dyn Sdf>,
+    period: Vec3,
+}
+
+impl Repeat {
+    pub fn new(sdf: Rc<dyn Sdf>, period: Vec3) -> Self {
+        Self { sdf, period }
+    }
+}
+
+impl Sdf for Repeat {
+    fn distance(&self, p: &Point3) -> f64 {
+        let wrap = |x: f64, period: f64| x - period * (x / period).round();
+        self.sdf.distance(&Point3::new(
+            wrap(p.x(), self.period.x()),
+            wrap(p.y(), self.period.y()),
+            wrap(p.z(), self.period.z()),
+        ))
+    }
+}
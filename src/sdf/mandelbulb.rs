@@ -0,0 +1,46 @@
+use crate::point3::Point3;
+use crate::sdf::Sdf;
+
+const BAILOUT: f64 = 2.0;
+
+/// Distance estimate of the Mandelbulb fractal, which fits inside a sphere of
+/// radius 1.2 around the origin.
+pub struct Mandelbulb {
+    power: f64,
+    iterations: usize,
+}
+
+impl Mandelbulb {
+    pub fn new(power: f64, iterations: usize) -> Self {
+        Self { power, iterations }
+    }
+}
+
+impl Sdf for Mandelbulb {
+    fn distance(&self, p: &Point3) -> f64 {
+        let (mut x, mut y, mut z) = (p.x(), p.y(), p.z());
+        let mut dr = 1.0;
+        let mut r = 0.0;
+
+        for _ in 0..self.iterations {
+            r = (x * x + y * y + z * z).sqrt();
+            if r > BAILOUT || r == 0.0 {
+                break;
+            }
+
+            let theta = (z / r).acos() * self.power;
+            let phi = y.atan2(x) * self.power;
+            dr = r.powf(self.power - 1.0) * self.power * dr + 1.0;
+
+            let zr = r.powf(self.power);
+            x = zr * theta.sin() * phi.cos() + p.x();
+            y = zr * theta.sin() * phi.sin() + p.y();
+            z = zr * theta.cos() + p.z();
+        }
+
+        if r == 0.0 {
+            return 0.0;
+        }
+        0.5 * r.ln() * r / dr
+    }
+}
@@ -0,0 +1,24 @@
+mod combinators;
+mod mandelbulb;
+mod primitives;
+
+use crate::point3::Point3;
+
+pub use combinators::{
+    Difference, Intersection, Onion, Repeat, Rotate, Round, Scale, SmoothDifference,
+    SmoothIntersection, SmoothUnion, Translate, Union,
+};
+pub use mandelbulb::Mandelbulb;
+pub use primitives::{BoxSdf, CapsuleSdf, SphereSdf, TorusSdf};
+
+/// A signed distance function: negative inside the shape, positive outside, and
+/// never larger than the true distance to the surface.
+pub trait Sdf {
+    fn distance(&self, p: &Point3) -> f64;
+}
+
+impl<F: Fn(&Point3) -> f64> Sdf for F {
+    fn distance(&self, p: &Point3) -> f64 {
+        self(p)
+    }
+}
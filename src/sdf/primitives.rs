@@ -0,0 +1,88 @@
+use crate::point3::Point3;
+use crate::sdf::Sdf;
+use crate::vec3::Vec3;
+
+fn length(x: f64, y: f64, z: f64) -> f64 {
+    (x * x + y * y + z * z).sqrt()
+}
+
+/// A sphere around the origin.
+pub struct SphereSdf {
+    radius: f64,
+}
+
+impl SphereSdf {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+}
+
+impl Sdf for SphereSdf {
+    fn distance(&self, p: &Point3) -> f64 {
+        length(p.x(), p.y(), p.z()) - self.radius
+    }
+}
+
+/// An axis aligned box around the origin.
+pub struct BoxSdf {
+    half_extents: Vec3,
+}
+
+impl BoxSdf {
+    pub fn new(half_extents: Vec3) -> Self {
+        Self { half_extents }
+    }
+}
+
+impl Sdf for BoxSdf {
+    fn distance(&self, p: &Point3) -> f64 {
+        let qx = p.x().abs() - self.half_extents.x();
+        let qy = p.y().abs() - self.half_extents.y();
+        let qz = p.z().abs() - self.half_extents.z();
+        length(qx.max(0.0), qy.max(0.0), qz.max(0.0)) + qx.max(qy).max(qz).min(0.0)
+    }
+}
+
+/// A torus around the origin with its ring in the xz plane.
+pub struct TorusSdf {
+    major_radius: f64,
+    minor_radius: f64,
+}
+
+impl TorusSdf {
+    pub fn new(major_radius: f64, minor_radius: f64) -> Self {
+        Self {
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl Sdf for TorusSdf {
+    fn distance(&self, p: &Point3) -> f64 {
+        let ring = (p.x() * p.x() + p.z() * p.z()).sqrt() - self.major_radius;
+        (ring * ring + p.y() * p.y()).sqrt() - self.minor_radius
+    }
+}
+
+/// The points within `radius` of the segment from `a` to `b`.
+pub struct CapsuleSdf {
+    a: Point3,
+    b: Point3,
+    radius: f64,
+}
+
+impl CapsuleSdf {
+    pub fn new(a: Point3, b: Point3, radius: f64) -> Self {
+        Self { a, b, radius }
+    }
+}
+
+impl Sdf for CapsuleSdf {
+    fn distance(&self, p: &Point3) -> f64 {
+        let pa = *p - self.a;
+        let ba = self.b - self.a;
+        let h = (pa.dot(&ba) / ba.dot(&ba)).clamp(0.0, 1.0);
+        (pa - h * ba).length() - self.radius
+    }
+}
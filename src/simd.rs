@@ -0,0 +1,666 @@
+//! Three-component vectors held in the lanes of SIMD registers, the storage
+//! behind [`Vec3`](crate::vec3::Vec3), [`Point3`](crate::point3::Point3) and
+//! [`Color`](crate::color::Color).
+//!
+//! Components are `f64` by default, or `f32` with the `f32` feature, which fits
+//! a whole vector in one SSE register. The public API of the math types stays
+//! `f64` either way. The fourth lane is padding and is never read, except by
+//! the wide BVH, which keeps one child box per lane and uses all four.
+//!
+//! The tests build every backend, whichever one the target and features pick.
+
+use std::fmt::{Debug, Formatter};
+use std::ops;
+
+#[cfg(feature = "f32")]
+pub(crate) type Float = f32;
+#[cfg(not(feature = "f32"))]
+pub(crate) type Float = f64;
+
+#[cfg(all(target_arch = "x86_64", any(feature = "f32", test)))]
+mod sse {
+    //! SSE is part of the x86_64 baseline, so its intrinsics are always safe
+    //! to call here.
+
+    use std::arch::x86_64::*;
+
+    /// All four lanes in one `__m128`.
+    #[derive(Copy, Clone)]
+    pub(crate) struct F3(__m128);
+
+    impl F3 {
+        #[inline]
+        pub(crate) fn from_lanes([x, y, z, w]: [f32; 4]) -> Self {
+            unsafe { Self(_mm_set_ps(w, z, y, x)) }
+        }
+
+        #[inline]
+        pub(crate) fn splat(v: f32) -> Self {
+            unsafe { Self(_mm_set1_ps(v)) }
+        }
+
+        #[inline]
+        pub(crate) fn lanes(self) -> [f32; 4] {
+            let mut lanes = [0.0; 4];
+            // SAFETY: `lanes` has room for the four unaligned floats stored.
+            unsafe { _mm_storeu_ps(lanes.as_mut_ptr(), self.0) };
+            lanes
+        }
+
+        #[inline]
+        pub(crate) fn add(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_add_ps(self.0, rhs.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn sub(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_sub_ps(self.0, rhs.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn mul(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_mul_ps(self.0, rhs.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn div(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_div_ps(self.0, rhs.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn min(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_min_ps(self.0, rhs.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn max(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_max_ps(self.0, rhs.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn sqrt(self) -> Self {
+            unsafe { Self(_mm_sqrt_ps(self.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn lt_mask(self, rhs: Self) -> u32 {
+            unsafe { _mm_movemask_ps(_mm_cmplt_ps(self.0, rhs.0)) as u32 }
+        }
+
+        #[inline]
+        pub(crate) fn lane_x(self) -> f32 {
+            unsafe { _mm_cvtss_f32(self.0) }
+        }
+
+        #[inline]
+        pub(crate) fn lane_y(self) -> f32 {
+            unsafe { _mm_cvtss_f32(_mm_shuffle_ps::<0b01_01_01_01>(self.0, self.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn lane_z(self) -> f32 {
+            unsafe { _mm_cvtss_f32(_mm_movehl_ps(self.0, self.0)) }
+        }
+
+        /// Combines `x`, `y` and `z` with a scalar operation.
+        #[inline]
+        fn reduce(self, op: impl Fn(__m128, __m128) -> __m128) -> f32 {
+            unsafe {
+                let y = _mm_shuffle_ps::<0b01_01_01_01>(self.0, self.0);
+                let z = _mm_movehl_ps(self.0, self.0);
+                _mm_cvtss_f32(op(op(self.0, y), z))
+            }
+        }
+
+        #[inline]
+        pub(crate) fn sum3(self) -> f32 {
+            self.reduce(|a, b| unsafe { _mm_add_ss(a, b) })
+        }
+
+        #[inline]
+        pub(crate) fn min3(self) -> f32 {
+            self.reduce(|a, b| unsafe { _mm_min_ss(a, b) })
+        }
+
+        #[inline]
+        pub(crate) fn max3(self) -> f32 {
+            self.reduce(|a, b| unsafe { _mm_max_ss(a, b) })
+        }
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", any(not(feature = "f32"), test)))]
+mod sse2 {
+    //! SSE2 is part of the x86_64 baseline, so its intrinsics are always safe
+    //! to call here.
+
+    use std::arch::x86_64::*;
+
+    /// `x` and `y` in one `__m128d`, `z` and the padding in the other.
+    #[derive(Copy, Clone)]
+    pub(crate) struct F3(__m128d, __m128d);
+
+    impl F3 {
+        #[inline]
+        pub(crate) fn from_lanes([x, y, z, w]: [f64; 4]) -> Self {
+            unsafe { Self(_mm_set_pd(y, x), _mm_set_pd(w, z)) }
+        }
+
+        #[inline]
+        pub(crate) fn splat(v: f64) -> Self {
+            unsafe { Self(_mm_set1_pd(v), _mm_set1_pd(v)) }
+        }
+
+        #[inline]
+        pub(crate) fn lanes(self) -> [f64; 4] {
+            let mut lanes = [0.0; 4];
+            // SAFETY: `lanes` has room for both pairs of unaligned doubles.
+            unsafe {
+                _mm_storeu_pd(lanes.as_mut_ptr(), self.0);
+                _mm_storeu_pd(lanes.as_mut_ptr().add(2), self.1);
+            }
+            lanes
+        }
+
+        #[inline]
+        pub(crate) fn add(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_add_pd(self.0, rhs.0), _mm_add_pd(self.1, rhs.1)) }
+        }
+
+        #[inline]
+        pub(crate) fn sub(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_sub_pd(self.0, rhs.0), _mm_sub_pd(self.1, rhs.1)) }
+        }
+
+        #[inline]
+        pub(crate) fn mul(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_mul_pd(self.0, rhs.0), _mm_mul_pd(self.1, rhs.1)) }
+        }
+
+        #[inline]
+        pub(crate) fn div(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_div_pd(self.0, rhs.0), _mm_div_pd(self.1, rhs.1)) }
+        }
+
+        #[inline]
+        pub(crate) fn min(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_min_pd(self.0, rhs.0), _mm_min_pd(self.1, rhs.1)) }
+        }
+
+        #[inline]
+        pub(crate) fn max(self, rhs: Self) -> Self {
+            unsafe { Self(_mm_max_pd(self.0, rhs.0), _mm_max_pd(self.1, rhs.1)) }
+        }
+
+        #[inline]
+        pub(crate) fn sqrt(self) -> Self {
+            unsafe { Self(_mm_sqrt_pd(self.0), _mm_sqrt_pd(self.1)) }
+        }
+
+        #[inline]
+        pub(crate) fn lt_mask(self, rhs: Self) -> u32 {
+            unsafe {
+                let low = _mm_movemask_pd(_mm_cmplt_pd(self.0, rhs.0));
+                let high = _mm_movemask_pd(_mm_cmplt_pd(self.1, rhs.1));
+                (low | high << 2) as u32
+            }
+        }
+
+        #[inline]
+        pub(crate) fn lane_x(self) -> f64 {
+            unsafe { _mm_cvtsd_f64(self.0) }
+        }
+
+        #[inline]
+        pub(crate) fn lane_y(self) -> f64 {
+            unsafe { _mm_cvtsd_f64(_mm_unpackhi_pd(self.0, self.0)) }
+        }
+
+        #[inline]
+        pub(crate) fn lane_z(self) -> f64 {
+            unsafe { _mm_cvtsd_f64(self.1) }
+        }
+
+        /// Combines `x`, `y` and `z` with a scalar operation.
+        #[inline]
+        fn reduce(self, op: impl Fn(__m128d, __m128d) -> __m128d) -> f64 {
+            unsafe {
+                let y = _mm_unpackhi_pd(self.0, self.0);
+                _mm_cvtsd_f64(op(op(self.0, y), self.1))
+            }
+        }
+
+        #[inline]
+        pub(crate) fn sum3(self) -> f64 {
+            self.reduce(|a, b| unsafe { _mm_add_sd(a, b) })
+        }
+
+        #[inline]
+        pub(crate) fn min3(self) -> f64 {
+            self.reduce(|a, b| unsafe { _mm_min_sd(a, b) })
+        }
+
+        #[inline]
+        pub(crate) fn max3(self) -> f64 {
+            self.reduce(|a, b| unsafe { _mm_max_sd(a, b) })
+        }
+    }
+}
+
+#[cfg(any(not(target_arch = "x86_64"), test))]
+mod portable {
+    use super::Float;
+
+    /// Plain lanes, left for the compiler to vectorise.
+    #[derive(Copy, Clone)]
+    pub(crate) struct F3([Float; 4]);
+
+    impl F3 {
+        #[inline]
+        pub(crate) fn from_lanes(lanes: [Float; 4]) -> Self {
+            Self(lanes)
+        }
+
+        #[inline]
+        pub(crate) fn splat(v: Float) -> Self {
+            Self([v; 4])
+        }
+
+        #[inline]
+        pub(crate) fn lanes(self) -> [Float; 4] {
+            self.0
+        }
+
+        #[inline]
+        fn zip(self, rhs: Self, f: impl Fn(Float, Float) -> Float) -> Self {
+            Self(std::array::from_fn(|i| f(self.0[i], rhs.0[i])))
+        }
+
+        #[inline]
+        pub(crate) fn add(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| a + b)
+        }
+
+        #[inline]
+        pub(crate) fn sub(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| a - b)
+        }
+
+        #[inline]
+        pub(crate) fn mul(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| a * b)
+        }
+
+        #[inline]
+        pub(crate) fn div(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| a / b)
+        }
+
+        /// Like `minps`, the second operand wins unless the first is smaller.
+        #[inline]
+        pub(crate) fn min(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| if a < b { a } else { b })
+        }
+
+        /// Like `maxps`, the second operand wins unless the first is larger.
+        #[inline]
+        pub(crate) fn max(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| if a > b { a } else { b })
+        }
+
+        #[inline]
+        pub(crate) fn sqrt(self) -> Self {
+            Self(self.0.map(Float::sqrt))
+        }
+
+        /// Like `cmpltps` and `movmskps`, bit `i` is set where lane `i` is less
+        /// than in `rhs`, over all four lanes.
+        #[inline]
+        pub(crate) fn lt_mask(self, rhs: Self) -> u32 {
+            (0..4)
+                .filter(|&i| self.0[i] < rhs.0[i])
+                .map(|i| 1 << i)
+                .sum()
+        }
+
+        #[inline]
+        pub(crate) fn lane_x(self) -> Float {
+            self.0[0]
+        }
+
+        #[inline]
+        pub(crate) fn lane_y(self) -> Float {
+            self.0[1]
+        }
+
+        #[inline]
+        pub(crate) fn lane_z(self) -> Float {
+            self.0[2]
+        }
+
+        #[inline]
+        pub(crate) fn sum3(self) -> Float {
+            self.0[0] + self.0[1] + self.0[2]
+        }
+
+        #[inline]
+        pub(crate) fn min3(self) -> Float {
+            self.0[0].min(self.0[1]).min(self.0[2])
+        }
+
+        #[inline]
+        pub(crate) fn max3(self) -> Float {
+            self.0[0].max(self.0[1]).max(self.0[2])
+        }
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+use portable as backend;
+#[cfg(all(target_arch = "x86_64", feature = "f32"))]
+use sse as backend;
+#[cfg(all(target_arch = "x86_64", not(feature = "f32")))]
+use sse2 as backend;
+
+pub(crate) use backend::F3;
+
+// The casts between `Float` and `f64` only do something with the `f32` feature.
+#[allow(clippy::unnecessary_cast)]
+impl F3 {
+    #[inline]
+    pub(crate) fn new(x: f64, y: f64, z: f64) -> Self {
+        Self::from_lanes([x as Float, y as Float, z as Float, 0.0])
+    }
+
+    #[inline]
+    pub(crate) fn scalar(v: f64) -> Self {
+        Self::splat(v as Float)
+    }
+
+    /// All four lanes, including the padding one.
+    #[inline]
+    pub(crate) fn from_wide([x, y, z, w]: [f64; 4]) -> Self {
+        Self::from_lanes([x as Float, y as Float, z as Float, w as Float])
+    }
+
+    /// All four lanes, including the padding one.
+    #[inline]
+    pub(crate) fn wide(self) -> [f64; 4] {
+        self.lanes().map(|v| v as f64)
+    }
+
+    /// Component `i`, counting x as 0.
+    #[inline]
+    pub(crate) fn get(self, i: usize) -> f64 {
+        match i {
+            0 => self.x(),
+            1 => self.y(),
+            _ => self.z(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn x(self) -> f64 {
+        self.lane_x() as f64
+    }
+
+    #[inline]
+    pub(crate) fn y(self) -> f64 {
+        self.lane_y() as f64
+    }
+
+    #[inline]
+    pub(crate) fn z(self) -> f64 {
+        self.lane_z() as f64
+    }
+
+    #[inline]
+    pub(crate) fn dot(self, rhs: Self) -> f64 {
+        (self * rhs).sum3() as f64
+    }
+
+    #[inline]
+    pub(crate) fn cross(self, rhs: Self) -> Self {
+        let (x, y, z) = (self.lane_x(), self.lane_y(), self.lane_z());
+        let (ox, oy, oz) = (rhs.lane_x(), rhs.lane_y(), rhs.lane_z());
+        Self::from_lanes([y * oz - z * oy, z * ox - x * oz, x * oy - y * ox, 0.0])
+    }
+
+    /// The entry of a slab from its two crossings `self` and `rhs`, no earlier
+    /// than `bound`. Lanes where either crossing is NaN, from a ray lying in
+    /// one of the slab's planes, give `bound`.
+    #[inline]
+    pub(crate) fn slab_near(self, rhs: Self, bound: Self) -> Self {
+        // Lane-wise min returns its second operand on NaN, so whichever
+        // crossing is NaN reaches `max` in one of the two orders and is
+        // replaced by `bound` there, which is then the smaller.
+        self.min(rhs).max(bound).min(rhs.min(self).max(bound))
+    }
+
+    /// The exit of a slab from its two crossings `self` and `rhs`, no later
+    /// than `bound`. Lanes where either crossing is NaN give `bound`.
+    #[inline]
+    pub(crate) fn slab_far(self, rhs: Self, bound: Self) -> Self {
+        self.max(rhs).min(bound).max(rhs.max(self).min(bound))
+    }
+
+    /// The smallest of the three components. Must not be NaN.
+    #[inline]
+    pub(crate) fn min_component(self) -> f64 {
+        self.min3() as f64
+    }
+
+    /// The largest of the three components. Must not be NaN.
+    #[inline]
+    pub(crate) fn max_component(self) -> f64 {
+        self.max3() as f64
+    }
+}
+
+impl Default for F3 {
+    fn default() -> Self {
+        Self::splat(0.0)
+    }
+}
+
+impl Debug for F3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let [x, y, z, _] = self.lanes();
+        f.debug_tuple("F3").field(&x).field(&y).field(&z).finish()
+    }
+}
+
+impl ops::Add for F3 {
+    type Output = F3;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        F3::add(self, rhs)
+    }
+}
+
+impl ops::Sub for F3 {
+    type Output = F3;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        F3::sub(self, rhs)
+    }
+}
+
+impl ops::Mul for F3 {
+    type Output = F3;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        F3::mul(self, rhs)
+    }
+}
+
+impl ops::Div for F3 {
+    type Output = F3;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        F3::div(self, rhs)
+    }
+}
+
+impl ops::Neg for F3 {
+    type Output = F3;
+
+    /// Multiplies rather than subtracts, so that zeros change sign too.
+    #[inline]
+    fn neg(self) -> Self::Output {
+        self * F3::splat(-1.0)
+    }
+}
+
+/// Implements a binary operator on the math types for every combination of
+/// owned and borrowed operands, from one expression on the owned values.
+macro_rules! lanes_op {
+    ($op:ident, $method:ident, $lhs:ty, $rhs:ty, $out:ty, |$a:ident, $b:ident| $body:expr) => {
+        impl std::ops::$op<$rhs> for $lhs {
+            type Output = $out;
+
+            #[inline]
+            fn $method(self, rhs: $rhs) -> Self::Output {
+                let ($a, $b) = (self, rhs);
+                $body
+            }
+        }
+
+        impl std::ops::$op<&$rhs> for $lhs {
+            type Output = $out;
+
+            #[inline]
+            fn $method(self, rhs: &$rhs) -> Self::Output {
+                let ($a, $b) = (self, *rhs);
+                $body
+            }
+        }
+
+        impl std::ops::$op<$rhs> for &$lhs {
+            type Output = $out;
+
+            #[inline]
+            fn $method(self, rhs: $rhs) -> Self::Output {
+                let ($a, $b) = (*self, rhs);
+                $body
+            }
+        }
+
+        impl std::ops::$op<&$rhs> for &$lhs {
+            type Output = $out;
+
+            #[inline]
+            fn $method(self, rhs: &$rhs) -> Self::Output {
+                let ($a, $b) = (*self, *rhs);
+                $body
+            }
+        }
+    };
+}
+
+pub(crate) use lanes_op;
+
+#[cfg(test)]
+mod tests {
+    use super::F3;
+
+    /// Checks a backend against plain scalar arithmetic, lane by lane.
+    macro_rules! backend_tests {
+        ($backend:ident, $float:ty) => {
+            mod $backend {
+                use crate::simd::$backend::F3;
+
+                type Float = $float;
+
+                const NAN: Float = Float::NAN;
+
+                fn same(a: [Float; 4], b: [Float; 4]) -> bool {
+                    (0..4).all(|i| a[i] == b[i] || (a[i].is_nan() && b[i].is_nan()))
+                }
+
+                fn zip(
+                    a: [Float; 4],
+                    b: [Float; 4],
+                    f: impl Fn(Float, Float) -> Float,
+                ) -> [Float; 4] {
+                    std::array::from_fn(|i| f(a[i], b[i]))
+                }
+
+                #[test]
+                fn lanes_round_trip() {
+                    let v = F3::from_lanes([1.0, -2.0, 3.5, 4.0]);
+                    assert_eq!(v.lanes(), [1.0, -2.0, 3.5, 4.0]);
+                    assert_eq!((v.lane_x(), v.lane_y(), v.lane_z()), (1.0, -2.0, 3.5));
+                    assert_eq!(F3::splat(0.25).lanes(), [0.25; 4]);
+                }
+
+                #[test]
+                fn arithmetic_matches_scalar() {
+                    let a = [1.5, -2.0, 9.0, 0.5];
+                    let b = [0.25, 4.0, -3.0, 2.0];
+                    let (va, vb) = (F3::from_lanes(a), F3::from_lanes(b));
+                    assert_eq!(va.add(vb).lanes(), zip(a, b, |a, b| a + b));
+                    assert_eq!(va.sub(vb).lanes(), zip(a, b, |a, b| a - b));
+                    assert_eq!(va.mul(vb).lanes(), zip(a, b, |a, b| a * b));
+                    assert_eq!(va.div(vb).lanes(), zip(a, b, |a, b| a / b));
+                    assert_eq!(vb.mul(vb).sqrt().lanes(), b.map(Float::abs));
+                }
+
+                #[test]
+                fn min_and_max_give_the_second_operand_on_nan() {
+                    let a = [1.0, 2.0, NAN, 3.0];
+                    let b = [2.0, 1.0, 4.0, NAN];
+                    let (va, vb) = (F3::from_lanes(a), F3::from_lanes(b));
+                    let min = zip(a, b, |a, b| if a < b { a } else { b });
+                    let max = zip(a, b, |a, b| if a > b { a } else { b });
+                    assert!(same(va.min(vb).lanes(), min), "{:?}", va.min(vb).lanes());
+                    assert!(same(va.max(vb).lanes(), max), "{:?}", va.max(vb).lanes());
+                    assert!(same(vb.min(va).lanes(), [1.0, 1.0, NAN, 3.0]));
+                    assert!(same(vb.max(va).lanes(), [2.0, 2.0, NAN, 3.0]));
+                }
+
+                #[test]
+                fn lt_mask_covers_all_four_lanes() {
+                    let a = F3::from_lanes([1.0, 2.0, NAN, 3.0]);
+                    let b = F3::from_lanes([2.0, 1.0, 4.0, NAN]);
+                    assert_eq!(a.lt_mask(b), 0b0001);
+                    assert_eq!(b.lt_mask(a), 0b0010);
+                    let a = F3::from_lanes([0.0, 5.0, 0.0, 0.0]);
+                    assert_eq!(a.lt_mask(F3::splat(1.0)), 0b1101);
+                }
+
+                #[test]
+                fn reductions_ignore_the_fourth_lane() {
+                    let v = F3::from_lanes([3.0, -1.0, 2.0, -100.0]);
+                    assert_eq!(v.sum3(), 4.0);
+                    assert_eq!(v.min3(), -1.0);
+                    assert_eq!(F3::from_lanes([3.0, -1.0, 2.0, 100.0]).max3(), 3.0);
+                    assert_eq!(F3::from_lanes([-3.0, 1.0, 2.0, 100.0]).max3(), 2.0);
+                    assert_eq!(F3::from_lanes([1.0, 3.0, -2.0, -100.0]).min3(), -2.0);
+                }
+            }
+        };
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    backend_tests!(sse, f32);
+    #[cfg(target_arch = "x86_64")]
+    backend_tests!(sse2, f64);
+    backend_tests!(portable, crate::simd::Float);
+
+    #[test]
+    fn cross_matches_scalar() {
+        let (a, b) = ([1.5, -2.0, 3.0], [4.0, 0.5, -6.0]);
+        let expected = [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ];
+        let cross = F3::new(a[0], a[1], a[2]).cross(F3::new(b[0], b[1], b[2]));
+        assert_eq!([cross.x(), cross.y(), cross.z()], expected);
+    }
+}
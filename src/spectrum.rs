@@ -0,0 +1,135 @@
+use std::sync::OnceLock;
+
+use crate::color::Color;
+
+pub const LAMBDA_MIN: f64 = 380.0;
+pub const LAMBDA_MAX: f64 = 720.0;
+
+const BINS: usize = 10;
+
+// Smits, "An RGB-to-Spectrum Conversion for Reflectances" (1999).
+const WHITE: [f64; BINS] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const CYAN: [f64; BINS] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const MAGENTA: [f64; BINS] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const YELLOW: [f64; BINS] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const RED: [f64; BINS] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const GREEN: [f64; BINS] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const BLUE: [f64; BINS] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+const XYZ_TO_SRGB: [[f64; 3]; 3] = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.9692660, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+pub fn sample_wavelength(u: f64) -> f64 {
+    LAMBDA_MIN + u * (LAMBDA_MAX - LAMBDA_MIN)
+}
+
+fn basis(table: &[f64; BINS], lambda: f64) -> f64 {
+    let width = (LAMBDA_MAX - LAMBDA_MIN) / BINS as f64;
+    let x = ((lambda - LAMBDA_MIN) / width - 0.5).clamp(0.0, (BINS - 1) as f64);
+    let i = (x as usize).min(BINS - 2);
+    let f = x - i as f64;
+    table[i] * (1.0 - f) + table[i + 1] * f
+}
+
+/// Value of the smooth spectrum corresponding to `color` at `lambda` nanometres.
+pub fn rgb_to_spectral(color: &Color, lambda: f64) -> f64 {
+    let (r, g, b) = (color.r(), color.g(), color.b());
+    let at = |table| basis(table, lambda);
+
+    if r <= g && r <= b {
+        if g <= b {
+            r * at(&WHITE) + (g - r) * at(&CYAN) + (b - g) * at(&BLUE)
+        } else {
+            r * at(&WHITE) + (b - r) * at(&CYAN) + (g - b) * at(&GREEN)
+        }
+    } else if g <= r && g <= b {
+        if r <= b {
+            g * at(&WHITE) + (r - g) * at(&MAGENTA) + (b - r) * at(&BLUE)
+        } else {
+            g * at(&WHITE) + (b - g) * at(&MAGENTA) + (r - b) * at(&RED)
+        }
+    } else if r <= g {
+        b * at(&WHITE) + (r - b) * at(&YELLOW) + (g - r) * at(&GREEN)
+    } else {
+        b * at(&WHITE) + (g - b) * at(&YELLOW) + (r - g) * at(&RED)
+    }
+}
+
+/// Collapses `color` to its spectral value when the ray carries a wavelength.
+pub fn project(color: Color, wavelength: Option<f64>) -> Color {
+    match wavelength {
+        Some(lambda) => {
+            let s = rgb_to_spectral(&color, lambda);
+            Color::new(s, s, s)
+        }
+        None => color,
+    }
+}
+
+fn lobe(lambda: f64, mu: f64, sigma_lo: f64, sigma_hi: f64) -> f64 {
+    let sigma = if lambda < mu { sigma_lo } else { sigma_hi };
+    let t = (lambda - mu) / sigma;
+    (-0.5 * t * t).exp()
+}
+
+// Wyman, Sloan and Shirley, "Simple Analytic Approximations to the CIE XYZ
+// Color Matching Functions" (2013).
+fn cie_xyz(lambda: f64) -> [f64; 3] {
+    [
+        1.056 * lobe(lambda, 599.8, 37.9, 31.0) + 0.362 * lobe(lambda, 442.0, 16.0, 26.7)
+            - 0.065 * lobe(lambda, 501.1, 20.4, 26.2),
+        0.821 * lobe(lambda, 568.8, 46.9, 40.5) + 0.286 * lobe(lambda, 530.9, 16.3, 31.1),
+        1.217 * lobe(lambda, 437.0, 11.8, 36.0) + 0.681 * lobe(lambda, 459.0, 26.0, 13.8),
+    ]
+}
+
+fn cie_rgb(lambda: f64) -> [f64; 3] {
+    let xyz = cie_xyz(lambda);
+    XYZ_TO_SRGB.map(|row| row[0] * xyz[0] + row[1] * xyz[1] + row[2] * xyz[2])
+}
+
+/// The RGB response to a constant unit spectrum, used to keep white white.
+fn white_point() -> &'static [f64; 3] {
+    static WHITE_POINT: OnceLock<[f64; 3]> = OnceLock::new();
+    WHITE_POINT.get_or_init(|| {
+        let mut acc = [0.0; 3];
+        let steps = (LAMBDA_MAX - LAMBDA_MIN) as usize;
+        for i in 0..steps {
+            let rgb = cie_rgb(LAMBDA_MIN + i as f64 + 0.5);
+            for (a, c) in acc.iter_mut().zip(rgb) {
+                *a += c;
+            }
+        }
+        acc
+    })
+}
+
+/// Monte Carlo estimate of the RGB colour of `radiance` carried at a uniformly
+/// sampled wavelength `lambda`.
+pub fn wavelength_to_rgb(lambda: f64, radiance: f64) -> Color {
+    let rgb = cie_rgb(lambda);
+    let white = white_point();
+    let scale = radiance * (LAMBDA_MAX - LAMBDA_MIN);
+    Color::new(
+        scale * rgb[0] / white[0],
+        scale * rgb[1] / white[1],
+        scale * rgb[2] / white[2],
+    )
+}
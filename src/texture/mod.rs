@@ -1,6 +1,7 @@
 mod checker_texture;
 mod image_texture;
 mod marble;
+mod noise_field;
 mod noise_texture;
 mod solid_color;
 
@@ -10,6 +11,7 @@ use crate::point3::Point3;
 pub use checker_texture::CheckerTexture;
 pub use image_texture::ImageTexture;
 pub use marble::MarbleTexture;
+pub use noise_field::NoiseFieldTexture;
 pub use noise_texture::NoiseTexture;
 pub use solid_color::SolidColor;
 
@@ -0,0 +1,45 @@
+use crate::color::Color;
+use crate::noise::Noise;
+use crate::point3::Point3;
+use crate::texture::Texture;
+use std::rc::Rc;
+
+/// Blends between two colours by the value of any [`Noise`], from `low` at the
+/// bottom of its range to `high` at the top.
+pub struct NoiseFieldTexture {
+    scale: f64,
+    noise: Rc<dyn Noise>,
+    low: Color,
+    high: Color,
+}
+
+impl NoiseFieldTexture {
+    /// Black to white.
+    pub fn new(noise: Rc<dyn Noise>, scale: f64) -> Self {
+        Self::new_with_colours(
+            noise,
+            scale,
+            Color::new(0.0, 0.0, 0.0),
+            Color::new(1.0, 1.0, 1.0),
+        )
+    }
+
+    pub fn new_with_colours(noise: Rc<dyn Noise>, scale: f64, low: Color, high: Color) -> Self {
+        Self {
+            scale,
+            noise,
+            low,
+            high,
+        }
+    }
+}
+
+impl Texture for NoiseFieldTexture {
+    fn value(&self, _u: f64, _v: f64, p: &Point3) -> Color {
+        let range = self.noise.range();
+        let value = self.noise.noise(&(self.scale * p));
+        let t = ((value - range.min) / range.size()).clamp(0.0, 1.0);
+
+        (1.0 - t) * self.low + t * self.high
+    }
+}
@@ -12,10 +12,15 @@ pub struct NoiseTexture {
 }
 
 impl NoiseTexture {
-    pub(crate) fn new(scale: f64) -> Self {
+    pub fn new(scale: f64) -> Self {
+        Self::new_with_seed(scale, 0)
+    }
+
+    /// Like `new`, but with the noise pattern picked by `seed`.
+    pub fn new_with_seed(scale: f64, seed: u64) -> Self {
         Self {
             scale,
-            noise: Perlin::new(),
+            noise: Perlin::new(seed),
         }
     }
 }
@@ -0,0 +1,197 @@
+use crate::keyframes::Interpolate;
+use crate::point3::Point3;
+use crate::vec3::Vec3;
+
+/// A unit quaternion representing a rotation.
+#[derive(Copy, Clone, Debug)]
+pub struct Quaternion {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self {
+            w: 1.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+}
+
+impl Quaternion {
+    /// Rotation by `angle` degrees around `axis`.
+    pub fn from_axis_angle(axis: Vec3, angle: f64) -> Self {
+        let half = angle.to_radians() / 2.0;
+        let axis = axis.unit_vector() * half.sin();
+        Self {
+            w: half.cos(),
+            x: axis.x(),
+            y: axis.y(),
+            z: axis.z(),
+        }
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self {
+            w: self.w,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+
+    /// The rotation `other` followed by `self`.
+    pub fn then(&self, other: &Quaternion) -> Self {
+        let (a, b) = (other, self);
+        Self {
+            w: b.w * a.w - b.x * a.x - b.y * a.y - b.z * a.z,
+            x: b.w * a.x + b.x * a.w + b.y * a.z - b.z * a.y,
+            y: b.w * a.y - b.x * a.z + b.y * a.w + b.z * a.x,
+            z: b.w * a.z + b.x * a.y - b.y * a.x + b.z * a.w,
+        }
+    }
+
+    pub fn rotate(&self, v: &Vec3) -> Vec3 {
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = 2.0 * q.cross(v);
+        *v + self.w * t + q.cross(&t)
+    }
+
+    fn dot(&self, other: &Quaternion) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    fn normalized(&self) -> Self {
+        let length = self.dot(self).sqrt();
+        Self {
+            w: self.w / length,
+            x: self.x / length,
+            y: self.y / length,
+            z: self.z / length,
+        }
+    }
+
+    /// Spherical linear interpolation along the shortest arc.
+    pub fn slerp(&self, other: &Quaternion, t: f64) -> Self {
+        let mut cos_theta = self.dot(other);
+        let mut other = *other;
+        if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            other = Self {
+                w: -other.w,
+                x: -other.x,
+                y: -other.y,
+                z: -other.z,
+            };
+        }
+
+        let (a, b) = if cos_theta > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1.0 - t) * theta).sin() / sin_theta,
+                (t * theta).sin() / sin_theta,
+            )
+        };
+
+        Self {
+            w: a * self.w + b * other.w,
+            x: a * self.x + b * other.x,
+            y: a * self.y + b * other.y,
+            z: a * self.z + b * other.z,
+        }
+        .normalized()
+    }
+}
+
+/// Scaling, then rotation, then translation, mapping object space to world space.
+#[derive(Copy, Clone, Debug)]
+pub struct Transform {
+    translation: Vec3,
+    rotation: Quaternion,
+    scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::default(),
+            rotation: Quaternion::default(),
+            scale: Vec3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl Transform {
+    pub fn translate(mut self, offset: Vec3) -> Self {
+        self.translation = self.translation + offset;
+        self
+    }
+
+    /// Adds a rotation by `angle` degrees around `axis`.
+    pub fn rotate(mut self, axis: Vec3, angle: f64) -> Self {
+        self.rotation = Quaternion::from_axis_angle(axis, angle).then(&self.rotation);
+        self
+    }
+
+    pub fn scale(mut self, factors: Vec3) -> Self {
+        self.scale = Vec3::new(
+            self.scale.x() * factors.x(),
+            self.scale.y() * factors.y(),
+            self.scale.z() * factors.z(),
+        );
+        self
+    }
+
+    pub fn apply_vector(&self, v: &Vec3) -> Vec3 {
+        self.rotation.rotate(&Vec3::new(
+            v.x() * self.scale.x(),
+            v.y() * self.scale.y(),
+            v.z() * self.scale.z(),
+        ))
+    }
+
+    pub fn apply_point(&self, p: &Point3) -> Point3 {
+        (self.apply_vector(&(*p).into()) + self.translation).into()
+    }
+
+    /// Transforms a surface normal, which needs the inverse transpose.
+    pub fn apply_normal(&self, n: &Vec3) -> Vec3 {
+        self.rotation
+            .rotate(&Vec3::new(
+                n.x() / self.scale.x(),
+                n.y() / self.scale.y(),
+                n.z() / self.scale.z(),
+            ))
+            .unit_vector()
+    }
+
+    pub fn inverse_vector(&self, v: &Vec3) -> Vec3 {
+        let v = self.rotation.conjugate().rotate(v);
+        Vec3::new(
+            v.x() / self.scale.x(),
+            v.y() / self.scale.y(),
+            v.z() / self.scale.z(),
+        )
+    }
+
+    pub fn inverse_point(&self, p: &Point3) -> Point3 {
+        self.inverse_vector(&(*p - self.translation).into()).into()
+    }
+}
+
+impl Interpolate for Transform {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            translation: self.translation.interpolate(&other.translation, t),
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: self.scale.interpolate(&other.scale, t),
+        }
+    }
+}
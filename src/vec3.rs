@@ -1,48 +1,40 @@
-use std::ops;
-
 use rand::{thread_rng, Rng};
 
 use crate::color::Color;
 use crate::point3::Point3;
+use crate::simd::{lanes_op, F3};
 
-#[derive(Copy, Clone, Debug)]
-pub struct Vec3 {
-    x: f64,
-    y: f64,
-    z: f64,
-}
-
-impl Default for Vec3 {
-    fn default() -> Self {
-        Self {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        }
-    }
-}
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Vec3(F3);
 
 impl Vec3 {
+    #[inline]
     pub fn new(x: f64, y: f64, z: f64) -> Self {
-        Self { x, y, z }
+        Self(F3::new(x, y, z))
+    }
+
+    #[inline]
+    pub(crate) fn from_lanes(lanes: F3) -> Self {
+        Self(lanes)
+    }
+
+    #[inline]
+    pub(crate) fn lanes(&self) -> F3 {
+        self.0
     }
 
     pub fn random() -> Self {
         let mut rng = rand::thread_rng();
-        Self {
-            x: rng.gen::<f64>(),
-            y: rng.gen::<f64>(),
-            z: rng.gen::<f64>(),
-        }
+        Self::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>())
     }
 
     pub fn random_range(min: f64, max: f64) -> Self {
         let mut rng = rand::thread_rng();
-        Self {
-            x: rng.gen_range(min..max),
-            y: rng.gen_range(min..max),
-            z: rng.gen_range(min..max),
-        }
+        Self::new(
+            rng.gen_range(min..max),
+            rng.gen_range(min..max),
+            rng.gen_range(min..max),
+        )
     }
 
     pub fn random_in_unit_sphere() -> Self {
@@ -67,6 +59,31 @@ impl Vec3 {
         Self::random_in_unit_sphere().unit_vector()
     }
 
+    /// A uniformly distributed unit vector from a 2D sample.
+    pub fn sample_unit_vector((u1, u2): (f64, f64)) -> Self {
+        let z = 1.0 - 2.0 * u1;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * std::f64::consts::PI * u2;
+        Vec3::new(r * phi.cos(), r * phi.sin(), z)
+    }
+
+    /// A uniformly distributed point in the unit disk from a 2D sample, using
+    /// the concentric mapping so that strata stay compact.
+    pub fn sample_in_unit_disk((u1, u2): (f64, f64)) -> Self {
+        let (a, b) = (2.0 * u1 - 1.0, 2.0 * u2 - 1.0);
+        if a == 0.0 && b == 0.0 {
+            return Vec3::default();
+        }
+
+        let quarter_pi = std::f64::consts::FRAC_PI_4;
+        let (r, theta) = if a.abs() > b.abs() {
+            (a, quarter_pi * (b / a))
+        } else {
+            (b, 2.0 * quarter_pi - quarter_pi * (a / b))
+        };
+        Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
+    }
+
     pub fn random_on_hemisphere(normal: &Vec3) -> Self {
         let on_sphere = Self::random_in_unit_sphere();
         if on_sphere.dot(normal) > 0.0 {
@@ -78,197 +95,102 @@ impl Vec3 {
 }
 
 impl Vec3 {
+    #[inline]
     pub fn x(&self) -> f64 {
-        self.x
+        self.0.x()
     }
+    #[inline]
     pub fn y(&self) -> f64 {
-        self.y
+        self.0.y()
     }
+    #[inline]
     pub fn z(&self) -> f64 {
-        self.z
+        self.0.z()
+    }
+
+    /// The component along axis `n`, counting x as 0.
+    ///
+    /// # Panics
+    /// If `n` is not 0, 1 or 2.
+    #[inline]
+    pub fn axis(&self, n: i32) -> f64 {
+        assert!((0..3).contains(&n), "Invalid axis {} on Vec3", n);
+        self.0.get(n as usize)
     }
 
+    #[inline]
     pub fn unit_vector(&self) -> Vec3 {
         self / self.length()
     }
 
+    #[inline]
     pub fn reflected(&self, n: &Vec3) -> Vec3 {
         self - 2.0 * self.dot(n) * n
     }
     pub fn refracted(&self, n: &Vec3, etai_over_etat: f64) -> Vec3 {
         let cos_theta = (-self).dot(n).min(1.0);
-        let r_out_perp = etai_over_etat * (self + &(cos_theta * n));
+        let r_out_perp = etai_over_etat * (self + cos_theta * n);
         let r_out_parallel = -((1.0 - r_out_perp.length_squared()).abs()).sqrt() * n;
         r_out_perp + r_out_parallel
     }
 
+    #[inline]
     pub fn length(&self) -> f64 {
         self.length_squared().sqrt()
     }
 
+    #[inline]
     pub fn length_squared(&self) -> f64 {
-        self.x * self.x + self.y * self.y + self.z * self.z
+        self.0.dot(self.0)
     }
 
+    #[inline]
     pub fn dot(&self, other: &Self) -> f64 {
-        self.x * other.x + self.y * other.y + self.z * other.z
+        self.0.dot(other.0)
     }
 
+    #[inline]
     pub fn cross(&self, other: &Self) -> Self {
-        Self {
-            x: self.y * other.z - self.z * other.y,
-            y: self.z * other.x - self.x * other.z,
-            z: self.x * other.y - self.y * other.x,
-        }
+        Self(self.0.cross(other.0))
     }
 
+    #[inline]
     pub fn near_zero(&self) -> bool {
         let s = 1e-8;
-        (self.x.abs() < s) && (self.y.abs() < s) && (self.z.abs() < s)
+        (self.0 * self.0).max_component() < s * s
     }
 }
 
-impl ops::Mul<f64> for &Vec3 {
-    type Output = Vec3;
-
-    fn mul(self, rhs: f64) -> Self::Output {
-        Self::Output {
-            x: self.x * rhs,
-            y: self.y * rhs,
-            z: self.z * rhs,
-        }
-    }
-}
+lanes_op!(Mul, mul, Vec3, f64, Vec3, |a, b| Vec3(a.0 * F3::scalar(b)));
+lanes_op!(Mul, mul, f64, Vec3, Vec3, |a, b| Vec3(F3::scalar(a) * b.0));
+lanes_op!(Div, div, Vec3, f64, Vec3, |a, b| (1.0 / b) * a);
+lanes_op!(Add, add, Vec3, Vec3, Vec3, |a, b| Vec3(a.0 + b.0));
+lanes_op!(Sub, sub, Vec3, Vec3, Vec3, |a, b| Vec3(a.0 - b.0));
+lanes_op!(Add, add, Vec3, Color, Color, |a, b| Color::from_lanes(
+    a.0 + b.lanes()
+));
 
-impl ops::Mul<&Vec3> for f64 {
-    type Output = Vec3;
-
-    fn mul(self, rhs: &Vec3) -> Self::Output {
-        Self::Output {
-            x: rhs.x * self,
-            y: rhs.y * self,
-            z: rhs.z * self,
-        }
-    }
-}
-
-impl ops::Mul<Vec3> for f64 {
-    type Output = Vec3;
-
-    fn mul(self, rhs: Vec3) -> Self::Output {
-        Self::Output {
-            x: rhs.x * self,
-            y: rhs.y * self,
-            z: rhs.z * self,
-        }
-    }
-}
-
-impl ops::Div<f64> for &Vec3 {
-    type Output = Vec3;
-
-    fn div(self, rhs: f64) -> Self::Output {
-        (1.0 / rhs) * self
-    }
-}
-
-impl ops::Div<f64> for Vec3 {
-    type Output = Vec3;
-
-    fn div(self, rhs: f64) -> Self::Output {
-        (1.0 / rhs) * self
-    }
-}
-
-impl ops::Add for &Vec3 {
-    type Output = Vec3;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::Output {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
-}
-
-impl ops::Neg for &Vec3 {
+impl std::ops::Neg for Vec3 {
     type Output = Vec3;
 
+    #[inline]
     fn neg(self) -> Self::Output {
-        Self::Output {
-            x: -self.x,
-            y: -self.y,
-            z: -self.z,
-        }
+        Vec3(-self.0)
     }
 }
 
-impl ops::Neg for Vec3 {
+impl std::ops::Neg for &Vec3 {
     type Output = Vec3;
 
+    #[inline]
     fn neg(self) -> Self::Output {
-        -&self
-    }
-}
-
-impl ops::Add<Color> for Vec3 {
-    type Output = Color;
-
-    fn add(self, rhs: Color) -> Self::Output {
-        Self::Output::new(self.x + rhs.r(), self.y + rhs.g(), self.z + rhs.b())
-    }
-}
-
-impl ops::Add for Vec3 {
-    type Output = Vec3;
-
-    fn add(self, rhs: Vec3) -> Self::Output {
-        Self::Output::new(self.x + rhs.x(), self.y + rhs.y(), self.z + rhs.z())
-    }
-}
-
-impl ops::Sub<Vec3> for &Vec3 {
-    type Output = Vec3;
-
-    fn sub(self, rhs: Vec3) -> Self::Output {
-        Self::Output {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-            z: self.z - rhs.z,
-        }
-    }
-}
-
-impl ops::Mul<f64> for Vec3 {
-    type Output = Vec3;
-
-    fn mul(self, rhs: f64) -> Self::Output {
-        Self::Output {
-            x: self.x * rhs,
-            y: self.y * rhs,
-            z: self.z * rhs,
-        }
-    }
-}
-
-impl ops::Index<i32> for Vec3 {
-    type Output = f64;
-
-    fn index(&self, index: i32) -> &Self::Output {
-        match index {
-            0 => &self.x,
-            1 => &self.y,
-            2 => &self.z,
-            _ => {
-                panic!("Invalid Index on Vec3")
-            }
-        }
+        -*self
     }
 }
 
 impl From<Vec3> for Point3 {
+    #[inline]
     fn from(val: Vec3) -> Self {
-        Point3::new(val.x, val.y, val.z)
+        Point3::from_lanes(val.0)
     }
 }
@@ -0,0 +1,204 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rust_tracer::aabb::Aabb;
+use rust_tracer::color::Color;
+use rust_tracer::hit_record::HitRecord;
+use rust_tracer::hittable::{Bvh4, BvhNode, Hittable, HittableList, Sphere, PACKET_SIZE};
+use rust_tracer::interval::Interval;
+use rust_tracer::material::Lambertian;
+use rust_tracer::point3::Point3;
+use rust_tracer::ray::Ray;
+use rust_tracer::vec3::Vec3;
+
+/// Hit by no ray, but counts the rays which got past its box.
+struct Probe {
+    bbox: Aabb,
+    reached: Cell<usize>,
+}
+
+impl Probe {
+    fn new(min: Point3, max: Point3) -> Self {
+        Self {
+            bbox: Aabb::from_points(min, max),
+            reached: Cell::new(0),
+        }
+    }
+}
+
+impl Hittable for Probe {
+    fn hit<'a>(&'a self, _r: &Ray, _ray_t: Interval, _rec: &mut HitRecord<'a>) -> bool {
+        self.reached.set(self.reached.get() + 1);
+        false
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+/// Rays along each axis lying in each face plane of the box from -1 to 1 in
+/// the other two, going both ways and with both signs of zero.
+fn face_rays() -> Vec<Ray> {
+    let mut rays = Vec::new();
+    for axis in 0..3 {
+        let u = (axis + 1) % 3;
+        for face in [-1.0, 1.0] {
+            for way in [-1.0, 1.0] {
+                for zero in [0.0, -0.0] {
+                    let mut origin = [0.0; 3];
+                    origin[axis] = -5.0 * way;
+                    origin[u] = face;
+                    let mut direction = [zero; 3];
+                    direction[axis] = way;
+                    rays.push(Ray::new(
+                        Point3::new(origin[0], origin[1], origin[2]),
+                        Vec3::new(direction[0], direction[1], direction[2]),
+                        0.0,
+                    ));
+                }
+            }
+        }
+    }
+    rays
+}
+
+#[test]
+fn aabb_counts_rays_in_either_face_as_inside() {
+    let bbox = Aabb::from_points(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+    for r in face_rays() {
+        let mut ray_t = Interval::new(0.001, f64::INFINITY);
+        assert!(bbox.hit(&r, &mut ray_t), "missed {:?}", r);
+        assert_eq!((ray_t.min, ray_t.max), (4.0, 6.0), "{:?}", r);
+    }
+}
+
+#[test]
+fn bvhs_count_rays_in_either_face_as_inside() {
+    let probes = [
+        Rc::new(Probe::new(
+            Point3::new(-1.0, -1.0, -1.0),
+            Point3::new(1.0, 1.0, 1.0),
+        )),
+        Rc::new(Probe::new(
+            Point3::new(10.0, 10.0, 10.0),
+            Point3::new(11.0, 11.0, 11.0),
+        )),
+    ];
+    let list = || {
+        let mut list = HittableList::default();
+        for probe in &probes {
+            list.add(probe.clone());
+        }
+        list
+    };
+    let bvhs: [Box<dyn Hittable>; 2] = [
+        Box::new(BvhNode::from_hittable_list(list())),
+        Box::new(Bvh4::from_hittable_list(list())),
+    ];
+
+    for bvh in &bvhs {
+        for r in face_rays() {
+            probes[0].reached.set(0);
+            let mut rec = HitRecord::default();
+            bvh.hit(&r, Interval::new(0.001, f64::INFINITY), &mut rec);
+            assert_eq!(probes[0].reached.get(), 1, "missed {:?}", r);
+        }
+    }
+}
+
+/// Spheres scattered over a ground sphere as in the random spheres scene,
+/// some of them moving, each with its own material to tell them apart by.
+fn random_spheres(rng: &mut StdRng) -> HittableList {
+    let mut world = HittableList::default();
+    let material = || Rc::new(Lambertian::new_with_color(Color::new(0.5, 0.5, 0.5)));
+    world.add(Rc::new(
+        Sphere::new(Point3::new(0.0, -1000.0, -1.0), 1000.0, material()).unwrap(),
+    ));
+    for a in -11..11 {
+        for b in -11..11 {
+            let center = Point3::new(
+                a as f64 + 0.9 * rng.gen::<f64>(),
+                0.2,
+                b as f64 + 0.9 * rng.gen::<f64>(),
+            );
+            let sphere = if rng.gen_bool(0.5) {
+                let center2 = center + Vec3::new(0.0, rng.gen_range(0.0..0.2), 0.0);
+                Sphere::new_moving(center, center2, 0.2, material())
+            } else {
+                Sphere::new(center, 0.2, material())
+            };
+            world.add(Rc::new(sphere.unwrap()));
+        }
+    }
+    for x in [-4.0, 0.0, 4.0] {
+        world.add(Rc::new(
+            Sphere::new(Point3::new(x, 1.0, 0.0), 1.0, material()).unwrap(),
+        ));
+    }
+    world
+}
+
+fn same_hit(a: Option<&HitRecord>, b: Option<&HitRecord>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => {
+            a.t == b.t && std::ptr::addr_eq(a.material().unwrap(), b.material().unwrap())
+        }
+        _ => false,
+    }
+}
+
+#[test]
+fn bvhs_find_the_same_hits() {
+    let mut rng = StdRng::seed_from_u64(11);
+    let world = random_spheres(&mut rng);
+    let mut copy = HittableList::default();
+    for object in &world.objects {
+        copy.add(object.clone());
+    }
+    let bvh = BvhNode::from_hittable_list(copy);
+    let bvh4 = Bvh4::from_hittable_list(world);
+
+    // Camera rays from the usual view, and rays between random points in and
+    // around the scene.
+    let rays: Vec<Ray> = (0..16 * PACKET_SIZE)
+        .map(|k| {
+            let mut point = || {
+                Point3::new(
+                    rng.gen_range(-12.0..12.0),
+                    rng.gen_range(-0.5..3.0),
+                    rng.gen_range(-12.0..12.0),
+                )
+            };
+            let origin = if k % 2 == 0 {
+                Point3::new(13.0, 2.0, 3.0)
+            } else {
+                point()
+            };
+            Ray::new(origin, point() - origin, rng.gen())
+        })
+        .collect();
+    let ray_t = Interval::new(0.001, f64::INFINITY);
+
+    let mut hits = 0;
+    for packet in rays.chunks(PACKET_SIZE) {
+        let mut recs = [HitRecord::default(); PACKET_SIZE];
+        let mask = bvh4.hit_packet(packet, ray_t, &mut recs);
+        for (k, r) in packet.iter().enumerate() {
+            let mut expected = HitRecord::default();
+            let expected = bvh.hit(r, ray_t, &mut expected).then_some(expected);
+            let mut single = HitRecord::default();
+            let single = bvh4.hit(r, ray_t, &mut single).then_some(single);
+            let packed = (mask >> k & 1 == 1).then_some(recs[k]);
+            assert!(same_hit(single.as_ref(), expected.as_ref()), "{:?}", r);
+            assert!(same_hit(packed.as_ref(), expected.as_ref()), "{:?}", r);
+            hits += expected.is_some() as usize;
+        }
+    }
+    // Most rays hit something, but not all.
+    assert!(hits > rays.len() / 2 && hits < rays.len(), "{} hits", hits);
+}
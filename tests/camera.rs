@@ -0,0 +1,119 @@
+use std::rc::Rc;
+
+use rust_tracer::camera::Camera;
+use rust_tracer::color::Color;
+use rust_tracer::hittable::{HittableList, Sphere};
+use rust_tracer::keyframes::Keyframes;
+use rust_tracer::material::Lambertian;
+use rust_tracer::point3::Point3;
+use rust_tracer::progress::{Progress, ProgressObserver};
+use rust_tracer::projection::RealisticLens;
+use rust_tracer::transform::Transform;
+use rust_tracer::Error;
+
+struct Quiet;
+
+impl ProgressObserver for Quiet {
+    fn update(&self, _progress: &Progress) {}
+
+    fn finished(&self, _progress: &Progress, _cancelled: bool) {}
+
+    fn message(&self, _message: &str) {}
+}
+
+#[test]
+fn camera_motion_without_keys_is_rejected() {
+    let mut camera = Camera::default().motion(Keyframes::default());
+    let result = camera.render_tile(0..1, 0..1, &HittableList::default());
+    assert!(matches!(result, Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn camera_motion_with_a_key_renders() {
+    let motion = Keyframes::default().key(0.0, Transform::default());
+    let mut camera = Camera::default().samples_per_pixel(1).motion(motion);
+    let pixels = camera
+        .render_tile(0..2, 0..2, &HittableList::default())
+        .unwrap();
+    assert_eq!(pixels.len(), 4);
+}
+
+fn lens_scene() -> HittableList {
+    let mut world = HittableList::default();
+    for (z, color) in [
+        (-2.5, Color::new(0.8, 0.2, 0.2)),
+        (-8.0, Color::new(0.2, 0.2, 0.8)),
+    ] {
+        world.add(Rc::new(
+            Sphere::new(
+                Point3::new(0.0, 0.0, z),
+                0.5,
+                Rc::new(Lambertian::new_with_color(color)),
+            )
+            .unwrap(),
+        ));
+    }
+    world
+}
+
+fn lens_camera() -> Camera {
+    Camera::default()
+        .aspect_ratio(1.0)
+        .image_width(6)
+        .samples_per_pixel(4)
+        .max_depth(2)
+        .vfov(20.0)
+        .background(Color::new(0.7, 0.8, 1.0))
+        .lookfrom(Point3::new(0.0, 0.0, 0.0))
+        .lookat(Point3::new(0.0, 0.0, -1.0))
+        .lens(RealisticLens::double_gauss_50mm())
+        .progress(Rc::new(Quiet))
+}
+
+fn channels(pixels: Vec<Color>) -> Vec<[f64; 3]> {
+    pixels.iter().map(|p| [p.r(), p.g(), p.b()]).collect()
+}
+
+#[test]
+fn lens_camera_refocuses_between_renders() {
+    let world = lens_scene();
+    let mut camera = lens_camera().focus_dist(2.5);
+    let near = channels(camera.render_tile(0..6, 0..6, &world).unwrap());
+
+    let mut camera = camera.focus_dist(8.0);
+    let far = channels(camera.render_tile(0..6, 0..6, &world).unwrap());
+    let fresh = channels(
+        lens_camera()
+            .focus_dist(8.0)
+            .render_tile(0..6, 0..6, &world)
+            .unwrap(),
+    );
+    assert_eq!(far, fresh);
+    assert_ne!(far, near);
+}
+
+#[test]
+fn preview_on_a_public_address_is_rejected() {
+    let output = std::env::temp_dir().join("rust_tracer_public_preview.ppm");
+    let mut camera = Camera::default()
+        .image_width(2)
+        .samples_per_pixel(1)
+        .preview("0.0.0.0:0")
+        .progress(Rc::new(Quiet));
+    let result = camera.render(output.to_str().unwrap(), &HittableList::default());
+    assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    assert!(!output.exists());
+}
+
+#[test]
+fn preview_on_a_loopback_address_renders() {
+    let output = std::env::temp_dir().join("rust_tracer_loopback_preview.ppm");
+    let mut camera = Camera::default()
+        .image_width(2)
+        .samples_per_pixel(1)
+        .preview("127.0.0.1:0")
+        .progress(Rc::new(Quiet));
+    let result = camera.render(output.to_str().unwrap(), &HittableList::default());
+    let _ = std::fs::remove_file(&output);
+    result.unwrap();
+}
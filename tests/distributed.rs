@@ -0,0 +1,164 @@
+use std::cell::Cell;
+use std::fs;
+use std::io;
+use std::net::TcpListener;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use rust_tracer::aabb::Aabb;
+use rust_tracer::camera::Camera;
+use rust_tracer::color::Color;
+use rust_tracer::distributed::{run_worker, Coordinator};
+use rust_tracer::hit_record::HitRecord;
+use rust_tracer::hittable::{Hittable, HittableList, Sphere};
+use rust_tracer::interval::Interval;
+use rust_tracer::material::Lambertian;
+use rust_tracer::point3::Point3;
+use rust_tracer::progress::{Progress, ProgressObserver};
+use rust_tracer::ray::Ray;
+use rust_tracer::Error;
+
+thread_local! {
+    /// Set on the worker thread which is to die in the middle of its tile.
+    static DOOMED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Hit by no ray, but panics when traced on a doomed thread, taking the
+/// worker and its connection down with it.
+struct Tripwire;
+
+impl Hittable for Tripwire {
+    fn hit<'a>(&'a self, _r: &Ray, _ray_t: Interval, _rec: &mut HitRecord<'a>) -> bool {
+        if DOOMED.get() {
+            panic!("worker killed in the middle of a tile");
+        }
+        false
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0))
+    }
+}
+
+struct Quiet;
+
+impl ProgressObserver for Quiet {
+    fn update(&self, _progress: &Progress) {}
+
+    fn finished(&self, _progress: &Progress, _cancelled: bool) {}
+
+    fn message(&self, _message: &str) {}
+}
+
+fn scene(name: &str, _seed: u64) -> Option<(Camera, HittableList)> {
+    if name != "spheres" {
+        return None;
+    }
+    let mut world = HittableList::default();
+    world.add(Rc::new(Tripwire));
+    world.add(Rc::new(
+        Sphere::new(
+            Point3::new(0.0, -100.5, -1.0),
+            100.0,
+            Rc::new(Lambertian::new_with_color(Color::new(0.5, 0.6, 0.3))),
+        )
+        .unwrap(),
+    ));
+    world.add(Rc::new(
+        Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            Rc::new(Lambertian::new_with_color(Color::new(0.7, 0.2, 0.2))),
+        )
+        .unwrap(),
+    ));
+    let camera = Camera::default()
+        .aspect_ratio(4.0 / 3.0)
+        .image_width(40)
+        .samples_per_pixel(4)
+        .max_depth(4)
+        .background(Color::new(0.7, 0.8, 1.0))
+        .progress(Rc::new(Quiet));
+    Some((camera, world))
+}
+
+#[test]
+fn coordinator_reassigns_the_tile_of_a_lost_worker() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let output =
+        std::env::temp_dir().join(format!("rust_tracer_distributed_{}.ppm", address.port()));
+
+    // The doomed worker is done for before the other connects, so it is sure
+    // to have been handed a tile.
+    let workers = thread::spawn(move || {
+        let doomed = thread::spawn(move || {
+            DOOMED.set(true);
+            run_worker(address, scene)
+        });
+        assert!(doomed.join().is_err());
+        run_worker(address, scene)
+    });
+
+    Coordinator::new("spheres", 7)
+        .tile_size(8)
+        .progress(Rc::new(Quiet))
+        .render(listener, scene, output.to_str().unwrap())
+        .unwrap();
+    workers.join().unwrap().unwrap();
+
+    let (mut camera, world) = scene("spheres", 7).unwrap();
+    let (width, height) = camera.image_size().unwrap();
+    let mut expected = format!("P3\n{}\n{}\n255\n", width, height);
+    for pixel in camera.render_tile(0..width, 0..height, &world).unwrap() {
+        // The tiles travel as single precision.
+        let [r, g, b] = [pixel.r() as f32, pixel.g() as f32, pixel.b() as f32];
+        let pixel = Color::new(r as f64, g as f64, b as f64);
+        expected += &format!("{}\n", pixel.gamma());
+    }
+
+    let rendered = fs::read_to_string(&output).unwrap();
+    let _ = fs::remove_file(&output);
+    assert!(rendered == expected, "distributed render differs");
+}
+
+fn assert_idle_timeout(result: rust_tracer::Result<()>, output: &std::path::Path) {
+    match result {
+        Err(Error::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::TimedOut),
+        _ => panic!("render did not time out"),
+    }
+    assert!(!output.exists());
+}
+
+#[test]
+fn coordinator_gives_up_without_workers() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let output = std::env::temp_dir().join("rust_tracer_distributed_no_workers.ppm");
+
+    let result = Coordinator::new("spheres", 7)
+        .idle_timeout(Duration::from_millis(200))
+        .progress(Rc::new(Quiet))
+        .render(listener, scene, output.to_str().unwrap());
+    assert_idle_timeout(result, &output);
+}
+
+#[test]
+fn coordinator_gives_up_once_every_worker_is_lost() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let output = std::env::temp_dir().join("rust_tracer_distributed_lost_workers.ppm");
+
+    let doomed = thread::spawn(move || {
+        DOOMED.set(true);
+        run_worker(address, scene)
+    });
+
+    let result = Coordinator::new("spheres", 7)
+        .tile_size(8)
+        .idle_timeout(Duration::from_millis(500))
+        .progress(Rc::new(Quiet))
+        .render(listener, scene, output.to_str().unwrap());
+    assert!(doomed.join().is_err());
+    assert_idle_timeout(result, &output);
+}
@@ -0,0 +1,96 @@
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rust_tracer::color::Color;
+use rust_tracer::hit_record::HitRecord;
+use rust_tracer::hittable::{Cone, Cylinder, Hittable, Torus};
+use rust_tracer::interval::Interval;
+use rust_tracer::material::Lambertian;
+use rust_tracer::point3::Point3;
+use rust_tracer::ray::Ray;
+use rust_tracer::vec3::Vec3;
+
+/// Deferred surface data is found again from the stored hit point, which is
+/// only single precision with the `f32` feature.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-5 * (1.0 + a.abs().max(b.abs()))
+}
+
+fn close_vec(a: Vec3, b: Vec3) -> bool {
+    close(a.x(), b.x()) && close(a.y(), b.y()) && close(a.z(), b.z())
+}
+
+/// Checks that the surface data a shape leaves for later, once filled in,
+/// matches the crossings it computes in full for CSG.
+fn deferred_surface_matches_spans(shape: &dyn Hittable) {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut hits = 0;
+    for _ in 0..2000 {
+        let mut point = |scale: f64| {
+            Point3::new(
+                rng.gen_range(-scale..scale),
+                rng.gen_range(-scale..scale),
+                rng.gen_range(-scale..scale),
+            )
+        };
+        let origin = point(6.0);
+        let r = Ray::new(origin, point(1.5) - origin, 0.0);
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        let mut rec = HitRecord::default();
+        let hit = shape.hit(&r, ray_t, &mut rec).then(|| {
+            rec.finish(&r);
+            rec
+        });
+        let spans = shape.spans(&r).unwrap();
+        let expected = spans
+            .iter()
+            .flat_map(|span| [span.enter, span.exit])
+            .find(|crossing| ray_t.surrounds(crossing.t));
+
+        match (hit, expected) {
+            (None, None) => {}
+            (Some(hit), Some(expected)) => {
+                hits += 1;
+                assert!(close(hit.t, expected.t), "{:?}", r);
+                assert!(close_vec(hit.normal, expected.normal), "{:?}", r);
+                assert!(
+                    close(hit.u, expected.u) && close(hit.v, expected.v),
+                    "{:?}",
+                    r
+                );
+                assert!(close_vec(hit.tangent, expected.tangent), "{:?}", r);
+                assert!(close_vec(hit.bitangent, expected.bitangent), "{:?}", r);
+            }
+            _ => panic!("hit and spans disagree on {:?}", r),
+        }
+    }
+    assert!(hits > 100, "only {} hits", hits);
+}
+
+fn material() -> Rc<Lambertian> {
+    Rc::new(Lambertian::new_with_color(Color::new(0.5, 0.5, 0.5)))
+}
+
+#[test]
+fn cone_defers_its_surface_data() {
+    let axis = Vec3::new(0.3, 1.5, -0.2);
+    let cone = Cone::new(Point3::new(0.1, -0.7, 0.2), axis, 1.0, true, material()).unwrap();
+    deferred_surface_matches_spans(&cone);
+}
+
+#[test]
+fn cylinder_defers_its_surface_data() {
+    let axis = Vec3::new(-0.4, 1.2, 0.5);
+    let cylinder = Cylinder::new(Point3::new(0.0, -0.6, 0.1), axis, 0.8, true, material()).unwrap();
+    deferred_surface_matches_spans(&cylinder);
+}
+
+#[test]
+fn torus_defers_its_surface_data() {
+    let axis = Vec3::new(0.2, 1.0, 0.6);
+    let torus = Torus::new(Point3::new(0.1, 0.0, -0.2), axis, 1.0, 0.3, material()).unwrap();
+    deferred_surface_matches_spans(&torus);
+}